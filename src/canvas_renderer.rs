@@ -0,0 +1,199 @@
+use crate::color::Color;
+use crate::frame_buffer::FrameBuffer;
+use crate::renderer::Renderer;
+use wasm_bindgen::{Clamped, JsCast};
+
+/// Draws on an HTML canvas element, at the full resolution of the display.
+pub struct CanvasRenderer {
+    canvas_element: web_sys::HtmlCanvasElement,
+    context: web_sys::CanvasRenderingContext2d,
+    size: f64,        // The width and height of the canvas in CSS pixels
+    pixel_ratio: f64, // Number of canvas pixels per CSS pixel
+    // The context's current styles, to avoid setting them for every cell
+    fill_color: Option<Color>,
+    stroke_color: Option<Color>,
+    line_width: Option<f64>,
+}
+
+impl CanvasRenderer {
+    pub fn new(canvas_id: &str) -> Self {
+        let canvas_element = Self::get_canvas_element(canvas_id);
+        let context = Self::get_canvas_rendering_context_2d(&canvas_element);
+        Self {
+            canvas_element,
+            context,
+            size: 0.0,
+            pixel_ratio: 1.0,
+            fill_color: None,
+            stroke_color: None,
+            line_width: None,
+        }
+    }
+    fn get_canvas_element(canvas_id: &str) -> web_sys::HtmlCanvasElement {
+        let window = web_sys::window().expect("should be a global `window`");
+        let document = window.document().expect("window should have a document");
+        let element = document
+            .get_element_by_id(canvas_id)
+            .unwrap_or_else(|| panic!("document should have an element with ID '{}'", canvas_id));
+        element
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .unwrap_or_else(|_| {
+                panic!(
+                    "element with ID '{}' should be an HTML canvas element",
+                    canvas_id
+                )
+            })
+    }
+    fn get_canvas_rendering_context_2d(
+        canvas_element: &web_sys::HtmlCanvasElement,
+    ) -> web_sys::CanvasRenderingContext2d {
+        canvas_element
+            .get_context("2d")
+            .unwrap()
+            .unwrap()
+            .dyn_into::<web_sys::CanvasRenderingContext2d>()
+            .expect("HTML canvas element should have a 2D context")
+    }
+    fn set_fill_color(&mut self, color: Color) {
+        if self.fill_color != Some(color) {
+            self.context.set_fill_style_str(&color.to_css());
+            self.fill_color = Some(color);
+        }
+    }
+    fn set_stroke(&mut self, color: Color, line_width: f64) {
+        if self.stroke_color != Some(color) {
+            self.context.set_stroke_style_str(&color.to_css());
+            self.stroke_color = Some(color);
+        }
+        if self.line_width != Some(line_width) {
+            self.context.set_line_width(line_width);
+            self.line_width = Some(line_width);
+        }
+    }
+    fn forget_styles(&mut self) {
+        self.fill_color = None;
+        self.stroke_color = None;
+        self.line_width = None;
+    }
+}
+
+impl Renderer for CanvasRenderer {
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color) {
+        self.set_fill_color(color);
+        self.context.fill_rect(x, y, width, height);
+    }
+    fn fill_rounded_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        radius: f64,
+        color: Color,
+    ) {
+        self.set_fill_color(color);
+        self.context.begin_path();
+        self.context
+            .round_rect_with_f64(x, y, width, height, radius)
+            .unwrap();
+        self.context.fill();
+    }
+    fn stroke_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        line_width: f64,
+        color: Color,
+    ) {
+        self.set_stroke(color, line_width);
+        self.context.stroke_rect(x, y, width, height);
+    }
+    /// Fills the squares as one path, so that there are no seams between them.
+    fn fill_cells(&mut self, corners: &[(f64, f64)], size: f64, color: Color) {
+        self.set_fill_color(color);
+        self.context.begin_path();
+        corners
+            .iter()
+            .for_each(|(x, y)| self.context.rect(*x, *y, size, size));
+        self.context.fill();
+    }
+    /// Clips to the squares and strokes them as one path.
+    fn stroke_cells(&mut self, corners: &[(f64, f64)], size: f64, line_width: f64, color: Color) {
+        if corners.is_empty() {
+            return;
+        }
+        // Styles set before saving the context survive restoring it
+        self.set_stroke(color, line_width);
+        self.context.save();
+        self.context.begin_path();
+        corners
+            .iter()
+            .for_each(|(x, y)| self.context.rect(*x, *y, size, size));
+        self.context.clip();
+        self.context.stroke();
+        self.context.restore();
+    }
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.context.save();
+        self.context.begin_path();
+        self.context.rect(x, y, width, height);
+        self.context.clip();
+    }
+    fn unclip(&mut self) {
+        // Restoring the context restores the styles from before the clip too
+        self.context.restore();
+        self.forget_styles();
+    }
+    /// Sizes the canvas for the current device pixel ratio, so that the grid is drawn at the
+    /// display's full resolution.
+    fn resize(&mut self, size: f64) {
+        self.size = size;
+        self.pixel_ratio = web_sys::window()
+            .map_or(1.0, |window| window.device_pixel_ratio())
+            .max(1.0);
+        let pixels = (size * self.pixel_ratio).round() as u32;
+        self.canvas_element.set_width(pixels);
+        self.canvas_element.set_height(pixels);
+        let style = self.canvas_element.style();
+        let css_size = format!("{}px", size);
+        style.set_property("width", &css_size).unwrap();
+        style.set_property("height", &css_size).unwrap();
+
+        // Resizing the canvas resets its context
+        self.context
+            .set_transform(self.pixel_ratio, 0.0, 0.0, self.pixel_ratio, 0.0, 0.0)
+            .unwrap();
+        self.forget_styles();
+    }
+    fn pixel_ratio(&self) -> f64 {
+        self.pixel_ratio
+    }
+    fn can_blit(&self) -> bool {
+        true
+    }
+    /// Puts the frame buffer's pixels on the canvas with a single `putImageData`.
+    fn blit(&mut self, frame_buffer: &FrameBuffer) {
+        let image_data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(
+            Clamped(frame_buffer.as_bytes()),
+            frame_buffer.width(),
+            frame_buffer.height(),
+        )
+        .unwrap();
+        self.context.put_image_data(&image_data, 0.0, 0.0).unwrap();
+    }
+    /// Maps the point through the canvas's bounding rectangle, in case CSS has scaled it.
+    fn point_from_client(&self, client_x: f64, client_y: f64) -> (f64, f64) {
+        let rect = self.canvas_element.get_bounding_client_rect();
+        let scale = if rect.width() > 0.0 {
+            self.size / rect.width()
+        } else {
+            1.0
+        };
+        (
+            (client_x - rect.left()) * scale,
+            (client_y - rect.top()) * scale,
+        )
+    }
+}
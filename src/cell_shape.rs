@@ -0,0 +1,10 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CellShape {
+    Square,
+    // A square with corners rounded to a quarter of its width
+    Rounded,
+    Circle,
+}
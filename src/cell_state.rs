@@ -1,9 +1,12 @@
 use wasm_bindgen::prelude::wasm_bindgen;
 
 #[wasm_bindgen]
+#[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum CellState {
-    Populated,
-    Vacant,
-    Invalid,
+    // The evolve kernels treat the states as bytes, so populated must be 1 and vacant 0.
+    // Invalid is only returned for cells outside the grid and is never stored.
+    Vacant = 0,
+    Populated = 1,
+    Invalid = 2,
 }
@@ -0,0 +1,27 @@
+/// An opaque RGB colour.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+impl Color {
+    pub const fn new(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+    /// The colour `fraction` of the way from this colour to `other`.
+    pub fn interpolate(self, other: Color, fraction: f64) -> Self {
+        let channel =
+            |from: u8, to: u8| (from as f64 + (to as f64 - from as f64) * fraction).round() as u8;
+        Self::new(
+            channel(self.red, other.red),
+            channel(self.green, other.green),
+            channel(self.blue, other.blue),
+        )
+    }
+    /// The colour as a CSS `rgb()` value.
+    pub fn to_css(self) -> String {
+        format!("rgb({}, {}, {})", self.red, self.green, self.blue)
+    }
+}
@@ -2,9 +2,16 @@ pub(crate) struct Constants;
 
 impl Constants {
     pub const BORDER_WIDTH: f64 = 10.0;
-    pub const BORDER_HIGHLIGHT_COLOR: &'static str = "rgb(255, 255, 170)";
-    pub const BORDER_COLOR: &'static str = "rgb(170, 170, 0)";
-    pub const VACANT_CELL_COLOR: &'static str = "rgb(170, 170, 170)";
-    pub const POPULATED_CELL_COLOR: &'static str = "rgb(10, 10, 0)";
-    pub const CELL_BORDER_COLOR: &'static str = "rgb(170, 170, 170)";
-}
\ No newline at end of file
+    pub const GRID_LINE_WIDTH: f64 = 0.25;
+    pub const MAJOR_GRID_LINE_WIDTH: f64 = 1.0;
+    // Grid lines closer together than this many device pixels are hidden
+    pub const MIN_GRID_LINE_SPACING: f64 = 4.0;
+    pub const OUTLINE_WIDTH: f64 = 1.0; // Width of outlines drawn over the cells
+    pub const MAX_ZOOM: f64 = 32.0;
+    // Cells smaller than this many device pixels are composed in memory and drawn as an image
+    pub const IMAGE_CELL_SIZE: f64 = 4.0;
+    pub const MAX_IMAGE_SIZE: u32 = 4096; // Pixels across the widest exported image
+    pub const SVG_CELL_SIZE: f64 = 10.0; // Pixels per cell in exported SVG documents
+    pub const HISTORY_BUDGET: usize = 16 * 1024 * 1024; // Bytes of undo history kept by default
+    pub const TRAIL_FADE: u8 = 16; // Fade of a trail per generation, out of 255
+}
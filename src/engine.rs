@@ -0,0 +1,15 @@
+use crate::cell_state::CellState;
+
+/// Computes successive generations for a `LifeGrid`.
+pub(crate) trait Engine {
+    /// Computes the generation that follows `cells`, a square grid with `cell_count` rows and
+    /// columns, and returns the index and new state of each cell whose state changes.
+    fn step(
+        &mut self,
+        cells: &[CellState],
+        cell_count: u32,
+        thread_count: u32,
+    ) -> Vec<(usize, CellState)>;
+    /// Called when the cell at `index` is set to `state` by anything other than `step`.
+    fn cell_edited(&mut self, _index: usize, _state: CellState) {}
+}
@@ -0,0 +1,10 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EngineKind {
+    // Computes every cell of every generation
+    Naive,
+    // Computes only the bricks of cells near the last changes
+    QuickLife,
+}
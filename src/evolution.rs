@@ -0,0 +1,190 @@
+use crate::cell_state::CellState;
+
+/// Computes the generation that follows `cells`, a square grid with `cell_count` rows and
+/// columns. The rows are split into `thread_count` horizontal bands which are computed in
+/// parallel when the platform supports it. Each band only reads the current generation, so
+/// the result is the same whatever the number of threads.
+pub(crate) fn next_generation(
+    cells: &[CellState],
+    cell_count: u32,
+    thread_count: u32,
+) -> Vec<CellState> {
+    let mut next_generation = vec![CellState::Vacant; cells.len()];
+    let row_length = cell_count as usize;
+    if row_length == 0 {
+        return next_generation;
+    }
+    let thread_count = thread_count.clamp(1, cell_count) as usize;
+    let band_height = row_length.div_ceil(thread_count);
+    let bands: Vec<(u32, &mut [CellState])> = next_generation
+        .chunks_mut(band_height * row_length)
+        .enumerate()
+        .map(|(index, band)| ((index * band_height) as u32, band))
+        .collect();
+    if bands.len() == 1 {
+        bands
+            .into_iter()
+            .for_each(|(first_row, band)| evolve_band(cells, cell_count, first_row, band));
+    } else {
+        evolve_bands(cells, cell_count, bands);
+    }
+    next_generation
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn evolve_bands(cells: &[CellState], cell_count: u32, bands: Vec<(u32, &mut [CellState])>) {
+    // No more threads than can run at once, each taking every nth band
+    let thread_count = std::thread::available_parallelism()
+        .map_or(1, |count| count.get())
+        .min(bands.len());
+    let mut groups: Vec<Vec<(u32, &mut [CellState])>> =
+        (0..thread_count).map(|_| vec![]).collect();
+    bands
+        .into_iter()
+        .enumerate()
+        .for_each(|(index, band)| groups[index % thread_count].push(band));
+    std::thread::scope(|scope| {
+        groups.into_iter().for_each(|group| {
+            scope.spawn(move || {
+                group
+                    .into_iter()
+                    .for_each(|(first_row, band)| evolve_band(cells, cell_count, first_row, band))
+            });
+        })
+    });
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm-threads"))]
+fn evolve_bands(cells: &[CellState], cell_count: u32, bands: Vec<(u32, &mut [CellState])>) {
+    use rayon::prelude::*;
+
+    bands
+        .into_par_iter()
+        .for_each(|(first_row, band)| evolve_band(cells, cell_count, first_row, band));
+}
+
+#[cfg(all(target_arch = "wasm32", not(feature = "wasm-threads")))]
+fn evolve_bands(cells: &[CellState], cell_count: u32, bands: Vec<(u32, &mut [CellState])>) {
+    bands
+        .into_iter()
+        .for_each(|(first_row, band)| evolve_band(cells, cell_count, first_row, band));
+}
+
+/// Fills `band`, the rows of the next generation starting at `first_row`.
+fn evolve_band(cells: &[CellState], cell_count: u32, first_row: u32, band: &mut [CellState]) {
+    let row_length = cell_count as usize;
+    let cells = as_bytes(cells);
+    // SAFETY: the kernels only ever store 0 or 1, which are `Vacant` and `Populated`
+    let band = unsafe { std::slice::from_raw_parts_mut(band.as_mut_ptr() as *mut u8, band.len()) };
+
+    // Rows outside the grid are treated as vacant
+    let empty_row = vec![0u8; row_length];
+    // Vertical sums of each column, with a vacant column on either side
+    let mut column_sums = vec![0u8; row_length + 2];
+
+    band.chunks_mut(row_length)
+        .enumerate()
+        .for_each(|(offset, next_row)| {
+            let row = first_row as usize + offset;
+            let current = &cells[row * row_length..(row + 1) * row_length];
+            let above = if row > 0 {
+                &cells[(row - 1) * row_length..row * row_length]
+            } else {
+                &empty_row
+            };
+            let below = if row + 1 < row_length {
+                &cells[(row + 1) * row_length..(row + 2) * row_length]
+            } else {
+                &empty_row
+            };
+            kernel::sum_columns(above, current, below, &mut column_sums[1..=row_length]);
+            kernel::apply_rules(current, &column_sums, next_row);
+        });
+}
+
+fn as_bytes(cells: &[CellState]) -> &[u8] {
+    // SAFETY: `CellState` is `repr(u8)`
+    unsafe { std::slice::from_raw_parts(cells.as_ptr() as *const u8, cells.len()) }
+}
+
+/// The neighbour-summing inner loop, operating on rows of 0 (vacant) and 1 (populated) bytes.
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128", feature = "simd")))]
+mod kernel {
+    /// Stores the sum of each column of the three rows in `sums`.
+    pub(super) fn sum_columns(above: &[u8], current: &[u8], below: &[u8], sums: &mut [u8]) {
+        sums.iter_mut().enumerate().for_each(|(col, sum)| {
+            *sum = above[col] + current[col] + below[col];
+        });
+    }
+
+    /// Stores the next state of each cell in `current` in `next`. `sums` holds the column sums
+    /// around `current`, offset by one so that each cell's block of nine starts at its index.
+    pub(super) fn apply_rules(current: &[u8], sums: &[u8], next: &mut [u8]) {
+        next.iter_mut().enumerate().for_each(|(col, next_state)| {
+            // The block includes the cell itself
+            let total = sums[col] + sums[col + 1] + sums[col + 2];
+            *next_state = (total == 3 || (current[col] == 1 && total == 4)) as u8;
+        });
+    }
+}
+
+/// The neighbour-summing inner loop using WebAssembly SIMD, sixteen cells at a time.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128", feature = "simd"))]
+mod kernel {
+    use core::arch::wasm32::*;
+
+    const LANES: usize = 16;
+
+    /// Stores the sum of each column of the three rows in `sums`.
+    pub(super) fn sum_columns(above: &[u8], current: &[u8], below: &[u8], sums: &mut [u8]) {
+        let vector_length = sums.len() - sums.len() % LANES;
+        (0..vector_length).step_by(LANES).for_each(|col| {
+            // SAFETY: `col + LANES` is within all four slices, and wasm loads and stores
+            // don't need to be aligned
+            unsafe {
+                let sum = u8x16_add(
+                    u8x16_add(load(above, col), load(current, col)),
+                    load(below, col),
+                );
+                v128_store(sums.as_mut_ptr().add(col) as *mut v128, sum);
+            }
+        });
+        (vector_length..sums.len()).for_each(|col| {
+            sums[col] = above[col] + current[col] + below[col];
+        });
+    }
+
+    /// Stores the next state of each cell in `current` in `next`. `sums` holds the column sums
+    /// around `current`, offset by one so that each cell's block of nine starts at its index.
+    pub(super) fn apply_rules(current: &[u8], sums: &[u8], next: &mut [u8]) {
+        let vector_length = next.len() - next.len() % LANES;
+        let one = u8x16_splat(1);
+        let three = u8x16_splat(3);
+        let four = u8x16_splat(4);
+        (0..vector_length).step_by(LANES).for_each(|col| {
+            // SAFETY: `sums` is two longer than `next` and `current`, so every load and store
+            // is in bounds
+            unsafe {
+                // The block includes the cell itself
+                let total = u8x16_add(
+                    u8x16_add(load(sums, col), load(sums, col + 1)),
+                    load(sums, col + 2),
+                );
+                let populated = u8x16_eq(load(current, col), one);
+                let survives = v128_or(
+                    u8x16_eq(total, three),
+                    v128_and(populated, u8x16_eq(total, four)),
+                );
+                v128_store(next.as_mut_ptr().add(col) as *mut v128, v128_and(survives, one));
+            }
+        });
+        (vector_length..next.len()).for_each(|col| {
+            let total = sums[col] + sums[col + 1] + sums[col + 2];
+            next[col] = (total == 3 || (current[col] == 1 && total == 4)) as u8;
+        });
+    }
+
+    unsafe fn load(bytes: &[u8], offset: usize) -> v128 {
+        v128_load(bytes.as_ptr().add(offset) as *const v128)
+    }
+}
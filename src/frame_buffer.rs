@@ -0,0 +1,207 @@
+use crate::color::Color;
+use crate::renderer::Renderer;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use wasm_bindgen::prelude::*;
+
+/// Draws into an in-memory RGBA image, four bytes per pixel in row-major order.
+#[wasm_bindgen]
+pub struct FrameBuffer {
+    width: u32,
+    height: u32,
+    scale: f64, // Number of pixels per grid pixel
+    pixels: Vec<u8>,
+    clip: Option<(u32, u32, u32, u32)>, // Left, top, right and bottom of the clip in pixels
+}
+
+#[wasm_bindgen]
+impl FrameBuffer {
+    /// A transparent image `width` by `height` pixels, drawn at `scale` pixels per grid pixel.
+    pub fn new(width: u32, height: u32, scale: f64) -> Self {
+        Self {
+            width,
+            height,
+            scale,
+            pixels: vec![0; width as usize * height as usize * 4],
+            clip: None,
+        }
+    }
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+    /// A copy of the image, e.g. for an `ImageData`.
+    pub fn pixels(&self) -> Vec<u8> {
+        self.pixels.clone()
+    }
+    /// The image encoded as a PNG file.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut png = vec![];
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .expect("PNG header should be written to memory");
+        writer
+            .write_image_data(&self.pixels)
+            .expect("PNG image data should be written to memory");
+        writer.finish().expect("PNG should be finished in memory");
+        png
+    }
+}
+
+impl FrameBuffer {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.pixels
+    }
+    /// The colour of the pixel at `x`, `y`, ignoring its alpha.
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        let offset = ((y * self.width + x) * 4) as usize;
+        Color::new(
+            self.pixels[offset],
+            self.pixels[offset + 1],
+            self.pixels[offset + 2],
+        )
+    }
+    /// The image as a frame of an animated GIF, in its own colours if there are no more than
+    /// a GIF palette's 256 of them, or else in the nearest of 256 chosen colours.
+    pub(crate) fn to_gif_frame(&self) -> gif::Frame<'static> {
+        let (width, height) = (self.width as u16, self.height as u16);
+        let mut palette: HashMap<[u8; 3], u8> = HashMap::new();
+        let mut colors = vec![];
+        let indexes: Option<Vec<u8>> = self
+            .pixels
+            .chunks(4)
+            .map(|pixel| {
+                let color = [pixel[0], pixel[1], pixel[2]];
+                if let Some(index) = palette.get(&color) {
+                    return Some(*index);
+                }
+                let index = u8::try_from(palette.len()).ok()?;
+                palette.insert(color, index);
+                colors.extend_from_slice(&color);
+                Some(index)
+            })
+            .collect();
+        match indexes {
+            Some(indexes) => gif::Frame::from_palette_pixels(width, height, indexes, colors, None),
+            None => gif::Frame::from_rgba_speed(width, height, &mut self.pixels.clone(), 10),
+        }
+    }
+    /// Converts a rectangle in grid pixels to pixels, limited to the image and the clip.
+    fn to_pixels(&self, x: f64, y: f64, width: f64, height: f64) -> (u32, u32, u32, u32) {
+        let (clip_left, clip_top, clip_right, clip_bottom) =
+            self.clip.unwrap_or((0, 0, self.width, self.height));
+        let pixel = |value: f64, low: u32, high: u32| {
+            ((value * self.scale).round().max(0.0) as u32).clamp(low, high)
+        };
+        (
+            pixel(x, clip_left, clip_right),
+            pixel(y, clip_top, clip_bottom),
+            pixel(x + width, clip_left, clip_right),
+            pixel(y + height, clip_top, clip_bottom),
+        )
+    }
+    /// Blends `color` over a rectangle of pixels with `coverage` between 0 and 1.
+    fn blend_pixels(&mut self, (left, top, right, bottom): (u32, u32, u32, u32), color: Color, coverage: f64) {
+        let channels = [color.red, color.green, color.blue];
+        (top..bottom).for_each(|y| {
+            (left..right).for_each(|x| {
+                let offset = ((y * self.width + x) * 4) as usize;
+                channels.iter().enumerate().for_each(|(channel, value)| {
+                    let old = self.pixels[offset + channel] as f64;
+                    self.pixels[offset + channel] =
+                        (old + (*value as f64 - old) * coverage).round() as u8;
+                });
+                self.pixels[offset + 3] = u8::MAX;
+            })
+        });
+    }
+    /// Draws a horizontal or vertical line centred on the given rectangle's edge, which is at
+    /// least a pixel thick. Lines thinner than that are blended in proportion to their width.
+    fn line(&mut self, x: f64, y: f64, width: f64, height: f64, line_width: f64, color: Color) {
+        let thickness = line_width * self.scale;
+        let coverage = thickness.min(1.0);
+        let half = thickness.max(1.0) / self.scale / 2.0;
+        let rect = if width == 0.0 {
+            self.to_pixels(x - half, y, half * 2.0, height)
+        } else {
+            self.to_pixels(x, y - half, width, half * 2.0)
+        };
+        self.blend_pixels(rect, color, coverage);
+    }
+}
+
+impl Renderer for FrameBuffer {
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color) {
+        let rect = self.to_pixels(x, y, width, height);
+        self.blend_pixels(rect, color, 1.0);
+    }
+    /// Fills the pixels whose centres are inside the rounded rectangle.
+    fn fill_rounded_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        radius: f64,
+        color: Color,
+    ) {
+        let (left, top, right, bottom) = self.to_pixels(x, y, width, height);
+        // The rectangle that the centres of the corners' circles lie on
+        let (inner_left, inner_top) = (x + radius, y + radius);
+        let (inner_right, inner_bottom) = (x + width - radius, y + height - radius);
+        (top..bottom).for_each(|row| {
+            (left..right).for_each(|col| {
+                let pixel_x = (col as f64 + 0.5) / self.scale;
+                let pixel_y = (row as f64 + 0.5) / self.scale;
+                let dx = (inner_left - pixel_x).max(pixel_x - inner_right).max(0.0);
+                let dy = (inner_top - pixel_y).max(pixel_y - inner_bottom).max(0.0);
+                if dx * dx + dy * dy <= radius * radius {
+                    self.blend_pixels((col, row, col + 1, row + 1), color, 1.0);
+                }
+            })
+        });
+    }
+    fn stroke_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        line_width: f64,
+        color: Color,
+    ) {
+        self.line(x, y, width, 0.0, line_width, color);
+        self.line(x, y + height, width, 0.0, line_width, color);
+        self.line(x, y, 0.0, height, line_width, color);
+        self.line(x + width, y, 0.0, height, line_width, color);
+    }
+    fn fill_cells(&mut self, corners: &[(f64, f64)], size: f64, color: Color) {
+        corners
+            .iter()
+            .for_each(|(x, y)| self.fill_rect(*x, *y, size, size, color));
+    }
+    /// Strokes each square clipped to its own pixels, so that every pixel only depends on the
+    /// square it is in.
+    fn stroke_cells(&mut self, corners: &[(f64, f64)], size: f64, line_width: f64, color: Color) {
+        let clip = self.clip;
+        corners.iter().for_each(|(x, y)| {
+            self.clip = Some(self.to_pixels(*x, *y, size, size));
+            self.stroke_rect(*x, *y, size, size, line_width, color);
+            self.clip = clip;
+        });
+    }
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.clip = Some(self.to_pixels(x, y, width, height));
+    }
+    fn unclip(&mut self) {
+        self.clip = None;
+    }
+    fn pixel_ratio(&self) -> f64 {
+        self.scale
+    }
+}
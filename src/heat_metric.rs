@@ -0,0 +1,10 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HeatMetric {
+    // Count the generations each cell has been populated
+    Alive,
+    // Count the times each cell has changed state
+    Flips,
+}
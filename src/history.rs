@@ -0,0 +1,136 @@
+use crate::cell_state::CellState;
+use std::collections::VecDeque;
+use std::mem::{size_of, size_of_val};
+
+/// Something that can be undone and redone.
+pub(crate) enum Action {
+    // Cells that were edited: their indexes and their states before and after
+    Edit(Vec<(usize, CellState, CellState)>),
+    // The states of every cell before a run of generations, or after it once undone
+    Snapshot(Vec<CellState>),
+}
+
+impl Action {
+    fn size(&self) -> usize {
+        match self {
+            Action::Edit(changes) => changes.len() * size_of::<(usize, CellState, CellState)>(),
+            Action::Snapshot(states) => states.len() * size_of::<CellState>(),
+        }
+    }
+}
+
+/// The actions that can be undone and redone, oldest first, within a memory budget. Edits are
+/// grouped into one action from the outermost `begin` to its `end`.
+pub(crate) struct History {
+    undo: VecDeque<Action>,
+    redo: Vec<Action>,
+    pending: Vec<(usize, CellState, CellState)>, // Edits since the outermost `begin`
+    depth: u32,                                  // Number of `begin`s without an `end`
+    budget: usize,                               // Most bytes the actions may take up
+    size: usize,                                 // Bytes the actions take up
+}
+
+impl History {
+    pub(crate) fn new(budget: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: vec![],
+            pending: vec![],
+            depth: 0,
+            budget,
+            size: 0,
+        }
+    }
+    pub(crate) fn begin(&mut self) {
+        self.depth += 1;
+    }
+    /// Ends the edit started by the matching `begin`, and records it if it's the outermost one.
+    pub(crate) fn end(&mut self) {
+        self.depth = self.depth.saturating_sub(1);
+        if self.depth == 0 && !self.pending.is_empty() {
+            let edit = Action::Edit(std::mem::take(&mut self.pending));
+            self.push(edit);
+        }
+    }
+    /// Records the change of the cell at `index` if an edit has begun and there's a budget for
+    /// it.
+    pub(crate) fn record(&mut self, index: usize, old_state: CellState, new_state: CellState) {
+        if self.depth > 0 && self.budget > 0 {
+            self.pending.push((index, old_state, new_state));
+        }
+    }
+    /// Records the cells before a generation, unless the last action was the start of the same
+    /// run of generations. Either way, the actions that could be redone no longer apply.
+    pub(crate) fn record_run(&mut self, states: &[CellState]) {
+        if matches!(self.undo.back(), Some(Action::Snapshot(_))) {
+            self.clear_redo();
+        } else if size_of_val(states) > self.budget {
+            // The cells wouldn't fit, and making room would forget everything else anyway
+            self.clear();
+        } else {
+            self.push(Action::Snapshot(states.to_vec()));
+        }
+    }
+    /// Adds an action to undo, forgetting the actions that could be redone and as many of the
+    /// oldest as it takes to stay within the budget.
+    fn push(&mut self, action: Action) {
+        self.clear_redo();
+        self.push_undo(action);
+    }
+    pub(crate) fn pop_undo(&mut self) -> Option<Action> {
+        let action = self.undo.pop_back()?;
+        self.size -= action.size();
+        Some(action)
+    }
+    pub(crate) fn pop_redo(&mut self) -> Option<Action> {
+        let action = self.redo.pop()?;
+        self.size -= action.size();
+        Some(action)
+    }
+    /// Adds an undone action to redo.
+    pub(crate) fn push_redo(&mut self, action: Action) {
+        self.size += action.size();
+        self.redo.push(action);
+        self.trim();
+    }
+    /// Adds a redone action to undo, keeping the rest that can be redone.
+    pub(crate) fn push_undo(&mut self, action: Action) {
+        self.size += action.size();
+        self.undo.push_back(action);
+        self.trim();
+    }
+    pub(crate) fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+    pub(crate) fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.size = 0;
+    }
+    pub(crate) fn budget(&self) -> usize {
+        self.budget
+    }
+    pub(crate) fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.trim();
+    }
+    fn clear_redo(&mut self) {
+        self.size -= self.redo.iter().map(Action::size).sum::<usize>();
+        self.redo.clear();
+    }
+    /// Forgets the oldest actions to undo, and then the furthest to redo, until the rest fit
+    /// in the budget.
+    fn trim(&mut self) {
+        while self.size > self.budget {
+            let action = match self.undo.pop_front() {
+                Some(action) => action,
+                None if !self.redo.is_empty() => self.redo.remove(0),
+                None => break,
+            };
+            self.size -= action.size();
+        }
+    }
+}
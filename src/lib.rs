@@ -1,13 +1,55 @@
+mod canvas_renderer;
 mod cell;
+mod cell_shape;
 mod cell_state;
+mod color;
 mod consts;
+mod engine;
+mod engine_kind;
+mod evolution;
+mod frame_buffer;
+mod heat_metric;
+mod history;
 mod life_grid;
+mod naive_engine;
+mod overlay_kind;
+mod quick_life_engine;
+mod renderer;
+mod shapes;
+mod svg_document;
 mod utils;
 mod pattern;
 mod pattern_parser;
+mod pattern_writer;
+mod placement_mode;
+mod text_mode;
+mod text_renderer;
+mod theme;
+mod viewport;
 
 use wasm_bindgen::prelude::*;
 
+pub use crate::canvas_renderer::CanvasRenderer;
+pub use crate::cell::Cell;
+pub use crate::cell_shape::CellShape;
+pub use crate::cell_state::CellState;
+pub use crate::color::Color;
+pub use crate::engine_kind::EngineKind;
+pub use crate::frame_buffer::FrameBuffer;
+pub use crate::heat_metric::HeatMetric;
+pub use crate::life_grid::LifeGrid;
+pub use crate::overlay_kind::OverlayKind;
+pub use crate::pattern::Pattern;
+pub use crate::pattern_parser::PatternParser;
+pub use crate::pattern_writer::PatternWriter;
+pub use crate::placement_mode::PlacementMode;
+pub use crate::renderer::Renderer;
+pub use crate::text_mode::TextMode;
+pub use crate::theme::Theme;
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm-threads"))]
+pub use wasm_bindgen_rayon::init_thread_pool;
+
 #[wasm_bindgen]
 extern "C" {
     // Use `js_namespace` here to bind `console.log(..)` instead of just
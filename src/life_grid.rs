@@ -1,50 +1,214 @@
+use crate::canvas_renderer::CanvasRenderer;
 use crate::cell::Cell;
+use crate::cell_shape::CellShape;
 use crate::cell_state::CellState;
+use crate::color::Color;
 use crate::consts::Constants;
+use crate::engine::Engine;
+use crate::engine_kind::EngineKind;
+use crate::frame_buffer::FrameBuffer;
+use crate::heat_metric::HeatMetric;
+use crate::history::{Action, History};
+use crate::naive_engine::NaiveEngine;
+use crate::overlay_kind::OverlayKind;
+use crate::pattern::Pattern;
+use crate::placement_mode::PlacementMode;
+use crate::quick_life_engine::QuickLifeEngine;
+use crate::renderer::Renderer;
+use crate::shapes;
+use crate::svg_document::SvgDocument;
+use crate::text_mode::TextMode;
+use crate::text_renderer::TextRenderer;
+use crate::theme::Theme;
+use crate::viewport::Viewport;
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::ops::Range;
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 pub struct LifeGrid {
     cell_states: Vec<CellState>,
+    generation: u32, // Number of evolves so far, which ages, trails and heat are measured from
+    changed_at: Vec<u32>, // Generation in which each cell last changed state
+    // Cells that changed in each of the last few generations and this one, oldest first, whose
+    // colours may still be changing with age or as their trails fade
+    recent_changes: VecDeque<Vec<usize>>,
+    // Activity count of each cell since the heat was last reset, not counting the generations
+    // a populated cell has been alive since `alive_since` under the alive metric
+    heat: Vec<u32>,
+    heat_max: u32, // Highest count in `heat`
+    heat_metric: HeatMetric,
+    alive_since: Vec<u32>, // Generation from which each populated cell's heat is counted
+    // How many populated cells have each `heat - alive_since`, which plus `generation` is their
+    // heat under the alive metric, so the hottest of them is found without visiting them all
+    live_heat: BTreeMap<i64, u32>,
     cell_count: u32, // Number of rows and columns of cells
     grid_size: f64,  // The width and height of the grid in pixels
-    context: web_sys::CanvasRenderingContext2d,
+    thread_count: u32, // Number of bands evolved in parallel
+    engine: Box<dyn Engine>,
+    engine_kind: EngineKind,
+    changed_cells: Vec<usize>, // Indexes of the cells that changed since the last draw
+    redraw_all: bool, // Whether the next draw must repaint the whole grid
+    theme: Theme,
+    age_coloring: bool, // Whether populated cells are coloured by age
+    overlay: OverlayKind, // How vacant cells show past activity
+    grid_lines: bool,     // Whether lines are drawn between the cells when they are large enough
+    major_grid_line_spacing: u32, // Number of cells between bold lines, or 0 for none
+    cell_shape: CellShape,
+    cell_gap: f64, // Pixels of vacant space around the shape of each cell
+    viewport: Viewport,
+    population: u32,
+    row_populations: Vec<u32>, // Number of populated cells in each row
+    col_populations: Vec<u32>, // Number of populated cells in each column
+    births: u32,               // Cells populated by the last evolve
+    deaths: u32,               // Cells vacated by the last evolve
+    renderer: Option<Box<dyn Renderer>>, // What `draw` draws on
+    image: Option<FrameBuffer>, // The last image drawn, when the cells are too small for vectors
+    minimap: Option<Box<dyn Renderer>>, // What `draw` draws the overview of the whole grid on
+    selection: Option<(Cell, Cell)>, // Top left and bottom right cells of the selection
+    clipboard: Option<Pattern>, // Cells copied from a selection, relative to its top left
+    history: History,           // Edits and runs of generations that can be undone
+    minimap_size: f64,                  // The width and height of the minimap in pixels
+    minimap_stale: bool,                // Whether the minimap needs repainting
 }
 
 #[wasm_bindgen]
 impl LifeGrid {
     pub fn new(grid_size: u32, cell_count: u32, canvas_id: &str) -> Self {
-        crate::utils::set_panic_hook();
-        let canvas_element = Self::get_canvas_element(canvas_id);
-        canvas_element.set_height(grid_size);
-        canvas_element.set_width(grid_size);
-
-        let context = Self::get_canvas_rendering_context_2d(&canvas_element);
-        let cell_states = (0..cell_count * cell_count)
-            .map(|_i| CellState::Vacant)
-            .collect();
-        Self {
-            cell_states,
+        Self::with_renderer(
+            grid_size,
             cell_count,
-            grid_size: grid_size as f64,
-            context,
+            Box::new(CanvasRenderer::new(canvas_id)),
+        )
+    }
+    /// A grid that isn't drawn anywhere except by `draw_to_frame_buffer` and the exports,
+    /// e.g. for use in a worker.
+    pub fn new_headless(grid_size: u32, cell_count: u32) -> Self {
+        Self::create(grid_size, cell_count, None)
+    }
+    /// Sizes the canvas for the current device pixel ratio, so that the grid is drawn at the
+    /// display's full resolution. Call this when the ratio changes, e.g. when the page is zoomed
+    /// or moved to another display.
+    pub fn refresh_pixel_ratio(&mut self) {
+        if let Some(renderer) = self.renderer.as_mut() {
+            renderer.resize(self.grid_size);
         }
+        if let Some(minimap) = self.minimap.as_mut() {
+            minimap.resize(self.minimap_size);
+        }
+        self.invalidate();
+    }
+    /// Repaints the cells that changed since the last draw. When the cells are only a few
+    /// pixels across, they are composed into an image which is drawn in one operation.
+    pub fn draw(&mut self) {
+        if let Some(mut renderer) = self.renderer.take() {
+            let device_cell_size = self.zoomed_cell_size() * renderer.pixel_ratio();
+            if renderer.can_blit() && device_cell_size < Constants::IMAGE_CELL_SIZE {
+                self.draw_image(renderer.as_mut());
+            } else {
+                self.image = None;
+                let changed_cells = if self.redraw_all {
+                    None
+                } else {
+                    Some(self.changed_cells.as_slice())
+                };
+                self.paint(renderer.as_mut(), changed_cells);
+            }
+            self.renderer = Some(renderer);
+        }
+        if self.minimap_stale {
+            if let Some(mut minimap) = self.minimap.take() {
+                self.draw_minimap(minimap.as_mut());
+                self.minimap = Some(minimap);
+            }
+            self.minimap_stale = false;
+        }
+        self.changed_cells.clear();
+        self.redraw_all = false;
+    }
+    /// Shows an overview of the whole grid, `size` pixels square, on the canvas with the ID
+    /// `canvas_id`, which `draw` keeps up to date.
+    pub fn set_minimap(&mut self, canvas_id: &str, size: u32) {
+        self.set_minimap_renderer(Box::new(CanvasRenderer::new(canvas_id)), size);
+    }
+    /// Centres the view on the part of the grid at a point on the minimap, in client
+    /// coordinates.
+    pub fn minimap_jump(&mut self, client_x: f64, client_y: f64) {
+        if let Some(minimap) = self.minimap.as_ref() {
+            let (x, y) = minimap.point_from_client(client_x, client_y);
+            let scale = self.active_grid_size() / self.minimap_size;
+            self.viewport
+                .center_on(x * scale, y * scale, self.active_grid_size());
+            self.invalidate();
+        }
+    }
+    /// Paints the whole grid into `frame_buffer`.
+    pub fn draw_to_frame_buffer(&self, frame_buffer: &mut FrameBuffer) {
+        self.draw_to(frame_buffer);
+    }
+    pub fn theme(&self) -> Theme {
+        self.theme.clone()
     }
-    pub fn draw(&self) {
-        self.draw_grid();
+    /// Changes the colours of the grid and redraws it.
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.theme = theme.clone();
+        self.invalidate();
+        self.draw();
+    }
+    /// Makes the next `draw` repaint the whole grid, e.g. after the canvas has been cleared.
+    pub fn invalidate(&mut self) {
+        self.redraw_all = true;
+        self.minimap_stale = true;
+        self.changed_cells.clear();
     }
     pub fn cell_state(&self, row: u32, col: u32) -> CellState {
         if self.in_grid(row as i32, col as i32) {
-            self.cell_states[self.cell_index(row, col)].clone()
+            self.cell_states[self.cell_index(row, col)]
         } else {
             CellState::Invalid
         }
     }
     pub fn set_cell_state(&mut self, row: u32, col: u32, state: CellState) {
+        self.history.begin();
         if self.in_grid(row as i32, col as i32) {
             let index = self.cell_index(row, col);
-            self.cell_states[index] = state;
+            self.set_state_at(index, state);
         }
+        self.history.end();
+    }
+    /// A pointer to the cell states, one byte per cell in row-major order, where 0 is vacant
+    /// and 1 is populated, for viewing from JavaScript without copying. The view must be
+    /// recreated after `evolve` or any growth of the WASM memory, and should be treated as
+    /// read-only; use `set_cells_from_bytes` to write the cells.
+    pub fn cells_ptr(&self) -> *const u8 {
+        self.cell_states.as_ptr() as *const u8
+    }
+    /// The number of bytes in the view returned by `cells_ptr`.
+    pub fn cells_len(&self) -> usize {
+        self.cell_states.len()
+    }
+    /// Sets every cell from `bytes`, one per cell in row-major order. Non-zero bytes are
+    /// populated, and cells beyond the end of `bytes` are vacated.
+    pub fn set_cells_from_bytes(&mut self, bytes: &[u8]) {
+        self.history.begin();
+        (0..self.cell_states.len()).for_each(|index| {
+            let state = match bytes.get(index) {
+                Some(byte) if *byte != 0 => CellState::Populated,
+                _ => CellState::Vacant,
+            };
+            self.set_state_at(index, state);
+        });
+        self.history.end();
+    }
+    /// The indexes (`row * cell_count + col`) of the populated cells.
+    pub fn live_cells_as_u32_array(&self) -> Vec<u32> {
+        self.cell_states
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state == CellState::Populated)
+            .map(|(index, _)| index as u32)
+            .collect()
     }
     pub fn toggle_cell_state(&mut self, row: u32, col: u32) {
         let new_state = match self.cell_state(row, col) {
@@ -53,37 +217,540 @@ impl LifeGrid {
         };
         self.set_cell_state(row, col, new_state);
     }
-    pub fn cell_from_point(&self, x: f64, y: f64) -> Cell {
-        Cell::new(
-            ((y - Constants::BORDER_WIDTH) / self.cell_size()) as u32,
-            ((x - Constants::BORDER_WIDTH) / self.cell_size()) as u32,
-        )
+    /// The cell at a point in client coordinates, e.g. a mouse event's `clientX` and
+    /// `clientY`, or nothing if the point isn't on a cell.
+    pub fn cell_from_point(&self, client_x: f64, client_y: f64) -> Option<Cell> {
+        let (x, y) = self.point_from_client(client_x, client_y);
+        let (x, y) = (x - Constants::BORDER_WIDTH, y - Constants::BORDER_WIDTH);
+        let active_grid_size = self.active_grid_size();
+        if x < 0.0 || y < 0.0 || x >= active_grid_size || y >= active_grid_size {
+            return None;
+        }
+        let (grid_x, grid_y) = self.viewport.to_grid(x, y);
+        let row = (grid_y / self.cell_size()) as u32;
+        let col = (grid_x / self.cell_size()) as u32;
+        if self.in_grid(row as i32, col as i32) {
+            Some(Cell::new(row, col))
+        } else {
+            None
+        }
+    }
+    pub fn zoom(&self) -> f64 {
+        self.viewport.zoom()
+    }
+    /// Multiplies the zoom by `factor`, keeping the grid under a point in client coordinates
+    /// in place.
+    pub fn zoom_at(&mut self, client_x: f64, client_y: f64, factor: f64) {
+        let (x, y) = self.point_from_client(client_x, client_y);
+        self.viewport.zoom_at(
+            x - Constants::BORDER_WIDTH,
+            y - Constants::BORDER_WIDTH,
+            factor,
+            self.active_grid_size(),
+        );
+        self.invalidate();
+    }
+    /// Moves the grid in the view by `dx`, `dy` client pixels.
+    pub fn pan_by(&mut self, dx: f64, dy: f64) {
+        let (x0, y0) = self.point_from_client(0.0, 0.0);
+        let (x1, y1) = self.point_from_client(dx, dy);
+        self.viewport
+            .pan_by(x1 - x0, y1 - y0, self.active_grid_size());
+        self.invalidate();
     }
+    /// Zooms out to show the whole grid.
+    pub fn reset_viewport(&mut self) {
+        self.viewport = Viewport::default();
+        self.invalidate();
+    }
+    /// Vacates every cell and forgets their activity.
     pub fn vacate_all_cells(&mut self) {
-        (0..self.cell_count * self.cell_count).for_each(|index| {
-            self.cell_states[index as usize] = CellState::Vacant;
-        })
+        self.history.begin();
+        self.vacate_cells();
+        self.reset_heat();
+        self.history.end();
     }
     pub fn evolve(&mut self) {
-        let mut next_generation: Vec<Cell> = vec![];
-        (0..self.cell_count).for_each(|row| {
-            (0..self.cell_count).for_each(|col| {
-                let state = self.cell_state(row, col);
-                let neighbors = self.count_neighbors(row, col);
-                if state == CellState::Populated && (neighbors == 2 || neighbors == 3) {
-                    next_generation.push(Cell::new(row, col));
-                } else if state == CellState::Vacant && neighbors == 3 {
-                    next_generation.push(Cell::new(row, col));
+        self.history.record_run(&self.cell_states);
+        let changes = self
+            .engine
+            .step(&self.cell_states, self.cell_count, self.thread_count);
+        self.births = 0;
+        self.deaths = 0;
+        let heat_max = self.current_heat_max();
+        self.generation = self.generation.wrapping_add(1);
+        self.recent_changes.push_back(vec![]);
+        if self.recent_changes.len() > Self::FADING_GENERATIONS as usize + 1 {
+            self.recent_changes.pop_front();
+        }
+        changes.into_iter().for_each(|(index, state)| {
+            if self.heat_metric == HeatMetric::Flips {
+                self.add_heat(index, 1);
+            }
+            if state == CellState::Populated {
+                self.births += 1;
+            } else if self.cell_states[index] == CellState::Populated {
+                self.deaths += 1;
+            }
+            self.record_change(index, state);
+            self.cell_states[index] = state;
+        });
+        if self.overlay == OverlayKind::Heatmap && self.current_heat_max() != heat_max {
+            // The scale of the whole map has changed
+            self.invalidate();
+        }
+        self.mark_fading_cells();
+    }
+    /// The number of generations the cell has been continuously populated.
+    pub fn cell_age(&self, row: u32, col: u32) -> u32 {
+        if self.in_grid(row as i32, col as i32) {
+            let index = self.cell_index(row, col);
+            if self.cell_states[index] == CellState::Populated {
+                self.generation.wrapping_sub(self.changed_at[index])
+            } else {
+                0
+            }
+        } else {
+            0
+        }
+    }
+    pub fn age_coloring(&self) -> bool {
+        self.age_coloring
+    }
+    /// Colours populated cells on a gradient by age instead of a single colour.
+    pub fn set_age_coloring(&mut self, age_coloring: bool) {
+        self.age_coloring = age_coloring;
+        self.invalidate();
+    }
+    pub fn overlay(&self) -> OverlayKind {
+        self.overlay
+    }
+    /// Shows past activity in the vacant cells, as a fading trail or as a heatmap.
+    pub fn set_overlay(&mut self, overlay: OverlayKind) {
+        self.overlay = overlay;
+        self.invalidate();
+    }
+    pub fn grid_lines(&self) -> bool {
+        self.grid_lines
+    }
+    /// Shows or hides the lines between the cells. Even when shown, they are hidden while the
+    /// cells are too small for them to be told apart.
+    pub fn set_grid_lines(&mut self, grid_lines: bool) {
+        self.grid_lines = grid_lines;
+        self.invalidate();
+    }
+    pub fn major_grid_line_spacing(&self) -> u32 {
+        self.major_grid_line_spacing
+    }
+    /// Draws a bold grid line every `spacing` cells, or none if `spacing` is 0.
+    pub fn set_major_grid_line_spacing(&mut self, spacing: u32) {
+        self.major_grid_line_spacing = spacing;
+        self.invalidate();
+    }
+    pub fn cell_shape(&self) -> CellShape {
+        self.cell_shape
+    }
+    pub fn set_cell_shape(&mut self, cell_shape: CellShape) {
+        self.cell_shape = cell_shape;
+        self.invalidate();
+    }
+    pub fn cell_gap(&self) -> f64 {
+        self.cell_gap
+    }
+    /// Leaves `gap` pixels between the shapes of neighbouring cells, up to half of a cell.
+    pub fn set_cell_gap(&mut self, gap: f64) {
+        self.cell_gap = gap.max(0.0);
+        self.invalidate();
+    }
+    pub fn heat_metric(&self) -> HeatMetric {
+        self.heat_metric
+    }
+    /// Changes what the heatmap counts, and starts counting again.
+    pub fn set_heat_metric(&mut self, heat_metric: HeatMetric) {
+        self.heat_metric = heat_metric;
+        self.reset_heat();
+    }
+    /// The activity count of every cell, in row-major order.
+    pub fn heat_counts(&self) -> Vec<u32> {
+        (0..self.heat.len()).map(|index| self.cell_heat(index)).collect()
+    }
+    /// Forgets the activity of every cell.
+    pub fn reset_heat(&mut self) {
+        self.heat.iter_mut().for_each(|heat| *heat = 0);
+        self.heat_max = 0;
+        self.live_heat.clear();
+        let faded = Self::faded_generation(self.generation);
+        (0..self.cell_states.len()).for_each(|index| {
+            if self.cell_states[index] == CellState::Populated {
+                if self.heat_metric == HeatMetric::Alive {
+                    self.alive_since[index] = self.generation;
+                    *self.live_heat.entry(self.live_heat_key(index)).or_insert(0) += 1;
                 }
-            })
+            } else {
+                // Clear the trail
+                self.changed_at[index] = faded;
+            }
         });
-        self.set_populated_cells(next_generation);
+        if self.overlay != OverlayKind::None {
+            self.invalidate();
+        }
+    }
+    pub fn engine(&self) -> EngineKind {
+        self.engine_kind
+    }
+    /// Switches the engine that computes the generations.
+    pub fn set_engine(&mut self, engine_kind: EngineKind) {
+        self.engine = match engine_kind {
+            EngineKind::Naive => Box::new(NaiveEngine),
+            EngineKind::QuickLife => {
+                Box::new(QuickLifeEngine::new(&self.cell_states, self.cell_count))
+            }
+        };
+        self.engine_kind = engine_kind;
+    }
+    /// The number of populated cells.
+    pub fn population(&self) -> u32 {
+        self.population
+    }
+    /// The number of cells populated by the last `evolve`.
+    pub fn births(&self) -> u32 {
+        self.births
+    }
+    /// The number of cells vacated by the last `evolve`.
+    pub fn deaths(&self) -> u32 {
+        self.deaths
+    }
+    /// The top left corner of the smallest rectangle containing every populated cell.
+    pub fn bounding_box_min(&self) -> Option<Cell> {
+        let row = self.row_populations.iter().position(|count| *count > 0)?;
+        let col = self.col_populations.iter().position(|count| *count > 0)?;
+        Some(Cell::new(row as u32, col as u32))
+    }
+    /// The bottom right corner of the smallest rectangle containing every populated cell.
+    pub fn bounding_box_max(&self) -> Option<Cell> {
+        let row = self.row_populations.iter().rposition(|count| *count > 0)?;
+        let col = self.col_populations.iter().rposition(|count| *count > 0)?;
+        Some(Cell::new(row as u32, col as u32))
+    }
+    /// Sets the cells on a line from `from` to `to` to `state`.
+    pub fn draw_line(&mut self, from: &Cell, to: &Cell, state: CellState) {
+        let cells = shapes::line(Self::point(from), Self::point(to), self.cell_count as i32);
+        self.set_states(cells, state);
+    }
+    /// Sets the cells of the rectangle with corners at `from` and `to`, or just its edges if
+    /// not `filled`, to `state`.
+    pub fn draw_rectangle(&mut self, from: &Cell, to: &Cell, filled: bool, state: CellState) {
+        let size = self.cell_count as i32;
+        let cells = shapes::rectangle(Self::point(from), Self::point(to), filled, size);
+        self.set_states(cells, state);
+    }
+    /// Sets the cells of the ellipse that fits in the rectangle with corners at `from` and
+    /// `to`, or just its edge if not `filled`, to `state`.
+    pub fn draw_ellipse(&mut self, from: &Cell, to: &Cell, filled: bool, state: CellState) {
+        let size = self.cell_count as i32;
+        let cells = shapes::ellipse(Self::point(from), Self::point(to), filled, size);
+        self.set_states(cells, state);
+    }
+    /// Sets the cell at `row`, `col` and the cells connected to it, across edges, that are in
+    /// the same state to `state`.
+    pub fn flood_fill(&mut self, row: u32, col: u32, state: CellState) {
+        let state = Self::stored_state(state);
+        let target = self.cell_state(row, col);
+        if target == CellState::Invalid || target == state {
+            return;
+        }
+        self.history.begin();
+        let mut pending = vec![(row as i32, col as i32)];
+        while let Some((row, col)) = pending.pop() {
+            if self.in_grid(row, col) && self.cell_state(row as u32, col as u32) == target {
+                self.set_cell_state(row as u32, col as u32, state);
+                pending.extend_from_slice(&[
+                    (row - 1, col),
+                    (row + 1, col),
+                    (row, col - 1),
+                    (row, col + 1),
+                ]);
+            }
+        }
+        self.history.end();
+    }
+    /// Undoes the last edit, or run of generations, and returns whether there was one.
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop_undo() {
+            Some(action) => {
+                let redo = self.apply(action, true);
+                self.history.push_redo(redo);
+                true
+            }
+            None => false,
+        }
+    }
+    /// Redoes the last action undone, and returns whether there was one.
+    pub fn redo(&mut self) -> bool {
+        match self.history.pop_redo() {
+            Some(action) => {
+                let undo = self.apply(action, false);
+                self.history.push_undo(undo);
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+    pub fn clear_history(&mut self) {
+        self.history.clear();
+    }
+    /// The most bytes of memory the undo history may use.
+    pub fn history_budget(&self) -> usize {
+        self.history.budget()
+    }
+    /// Limits the undo history to `budget` bytes, forgetting the oldest actions to fit.
+    pub fn set_history_budget(&mut self, budget: usize) {
+        self.history.set_budget(budget);
+    }
+    /// Selects the rectangle of cells with corners at `from` and `to`, in either order.
+    pub fn select(&mut self, from: &Cell, to: &Cell) {
+        let (rows, cols) = self.region(from, to);
+        self.selection = if rows.is_empty() || cols.is_empty() {
+            None
+        } else {
+            Some((
+                Cell::new(rows.start, cols.start),
+                Cell::new(rows.end - 1, cols.end - 1),
+            ))
+        };
+        self.invalidate();
+    }
+    pub fn select_all(&mut self) {
+        self.select(
+            &Cell::new(0, 0),
+            &Cell::new(self.cell_count.saturating_sub(1), self.cell_count.saturating_sub(1)),
+        );
+    }
+    pub fn deselect(&mut self) {
+        self.selection = None;
+        self.invalidate();
+    }
+    /// The top left cell of the selection.
+    pub fn selection_min(&self) -> Option<Cell> {
+        self.selection.as_ref().map(|(min, _)| min.clone())
+    }
+    /// The bottom right cell of the selection.
+    pub fn selection_max(&self) -> Option<Cell> {
+        self.selection.as_ref().map(|(_, max)| max.clone())
+    }
+    /// Copies the populated cells in the selection to the clipboard.
+    pub fn copy_selection(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            let cells = rows
+                .clone()
+                .flat_map(|row| cols.clone().map(move |col| (row, col)))
+                .filter(|(row, col)| self.cell_state(*row, *col) == CellState::Populated)
+                .map(|(row, col)| Cell::new(row - rows.start, col - cols.start))
+                .collect();
+            self.clipboard = Some(Pattern::new(vec![], cells));
+        }
+    }
+    /// Copies the populated cells in the selection to the clipboard and vacates them.
+    pub fn cut_selection(&mut self) {
+        self.copy_selection();
+        self.vacate_selection();
+    }
+    /// Populates the cells of the clipboard's pattern with its top left at `row`, `col`, and
+    /// selects them. Cells that would be past the edge of the grid are left out.
+    pub fn paste(&mut self, row: u32, col: u32) {
+        if let Some(pattern) = self.clipboard.take() {
+            self.place_pattern(&pattern, row, col, PlacementMode::Or, false);
+            let (height, width) = Self::pattern_size(&pattern);
+            let max = Cell::new(
+                row.saturating_add(height.saturating_sub(1)),
+                col.saturating_add(width.saturating_sub(1)),
+            );
+            self.select(&Cell::new(row, col), &max);
+            self.clipboard = Some(pattern);
+        }
+    }
+    /// Combines `pattern` with the cells already in the grid according to `mode`, with the
+    /// pattern's top left at `row`, `col`, or its centre there if `centered` is set. Returns the
+    /// number of the pattern's cells that fall outside the grid and are left out.
+    pub fn place_pattern(
+        &mut self,
+        pattern: &Pattern,
+        row: u32,
+        col: u32,
+        mode: PlacementMode,
+        centered: bool,
+    ) -> u32 {
+        let (height, width) = Self::pattern_size(pattern);
+        let (top, left) = if centered {
+            (row as i64 - height as i64 / 2, col as i64 - width as i64 / 2)
+        } else {
+            (row as i64, col as i64)
+        };
+        // Cells of the grid relative to the pattern's top left, if they are in the grid
+        let cell_count = self.cell_count as i64;
+        let grid_cell = |row: u32, col: u32| {
+            let (row, col) = (top + row as i64, left + col as i64);
+            if (0..cell_count).contains(&row) && (0..cell_count).contains(&col) {
+                Some((row as u32, col as u32))
+            } else {
+                None
+            }
+        };
+
+        self.history.begin();
+        if mode == PlacementMode::Replace {
+            (0..height).for_each(|row| {
+                (0..width).for_each(|col| {
+                    if let Some((row, col)) = grid_cell(row, col) {
+                        self.set_cell_state(row, col, CellState::Vacant);
+                    }
+                })
+            });
+        }
+        let mut clipped = 0;
+        pattern.cells().iter().for_each(|cell| match grid_cell(cell.row(), cell.col()) {
+            Some((row, col)) => {
+                let state = match (mode, self.cell_state(row, col)) {
+                    (PlacementMode::AndNot, _) => CellState::Vacant,
+                    (PlacementMode::Xor, CellState::Populated) => CellState::Vacant,
+                    _ => CellState::Populated,
+                };
+                self.set_cell_state(row, col, state);
+            }
+            None => clipped += 1,
+        });
+        self.history.end();
+        clipped
+    }
+    /// Vacates the cells in the selection.
+    pub fn vacate_selection(&mut self) {
+        self.history.begin();
+        if let Some((rows, cols)) = self.selected_cells() {
+            rows.for_each(|row| {
+                cols.clone()
+                    .for_each(|col| self.set_cell_state(row, col, CellState::Vacant))
+            });
+        }
+        self.history.end();
+    }
+    /// Vacates the cells outside the selection.
+    pub fn vacate_outside_selection(&mut self) {
+        self.history.begin();
+        if let Some((rows, cols)) = self.selected_cells() {
+            (0..self.cell_count).for_each(|row| {
+                (0..self.cell_count)
+                    .filter(|col| !rows.contains(&row) || !cols.contains(col))
+                    .for_each(|col| self.set_cell_state(row, col, CellState::Vacant))
+            });
+        }
+        self.history.end();
+    }
+    /// The pattern that `paste` inserts.
+    pub fn clipboard(&self) -> Option<Pattern> {
+        self.clipboard.clone()
+    }
+    pub fn set_clipboard(&mut self, pattern: &Pattern) {
+        self.clipboard = Some(pattern.clone());
+    }
+    /// An SVG document of the cells in the current theme and style, either the whole grid or
+    /// just the bounding box of the populated cells if `crop` is set.
+    pub fn to_svg(&self, crop: bool) -> String {
+        let (rows, cols) = self.export_region(crop);
+        self.svg(rows, cols)
+    }
+    /// An SVG document of the cells from `min` to `max` inclusive, e.g. a selection.
+    pub fn to_svg_region(&self, min: &Cell, max: &Cell) -> String {
+        let (rows, cols) = self.region(min, max);
+        self.svg(rows, cols)
+    }
+    /// A PNG image of the cells, `scale` pixels across each, in the current theme and style,
+    /// either of the whole grid or just the bounding box if `crop` is set.
+    pub fn to_png(&self, scale: u32, crop: bool) -> Vec<u8> {
+        let (rows, cols) = self.export_region(crop);
+        self.image_of(rows, cols, scale).to_png()
+    }
+    /// A PNG image of the cells from `min` to `max` inclusive, `scale` pixels across each.
+    pub fn to_png_region(&self, min: &Cell, max: &Cell, scale: u32) -> Vec<u8> {
+        let (rows, cols) = self.region(min, max);
+        self.image_of(rows, cols, scale).to_png()
+    }
+    /// The cells as lines of text, either the whole grid or just the bounding box if `crop` is
+    /// set.
+    pub fn to_text(&self, mode: TextMode, crop: bool) -> String {
+        let (rows, cols) = self.export_region(crop);
+        self.text(rows, cols, mode)
+    }
+    /// The cells from `min` to `max` inclusive as lines of text.
+    pub fn to_text_region(&self, min: &Cell, max: &Cell, mode: TextMode) -> String {
+        let (rows, cols) = self.region(min, max);
+        self.text(rows, cols, mode)
+    }
+    /// The cells that are at least partly in view as lines of text.
+    pub fn to_text_visible(&self, mode: TextMode) -> String {
+        let (rows, cols) = self.visible_cells();
+        self.text(rows, cols, mode)
+    }
+    /// An animated GIF of `frames` generations, starting with the current one, shown `delay`
+    /// milliseconds apart at `scale` pixels per cell. If `crop` is set, every frame is cropped
+    /// to the smallest rectangle containing all of the generations' populated cells. The
+    /// generations are computed on a copy of the grid, which is left as it is.
+    pub fn to_gif(&self, frames: u32, delay: u32, scale: u32, crop: bool) -> Vec<u8> {
+        let (rows, cols) = if crop {
+            self.run_bounds(frames)
+        } else {
+            self.export_region(false)
+        };
+        // GIF dimensions are 16 bits, which the largest images fit in
+        let scale = Self::image_scale(&rows, &cols, scale);
+        let width = (cols.len() as u32 * scale).max(1) as u16;
+        let height = (rows.len() as u32 * scale).max(1) as u16;
+
+        let mut gif = vec![];
+        let mut encoder = gif::Encoder::new(&mut gif, width, height, &[])
+            .expect("GIF header should be written to memory");
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .expect("GIF repeat count should be written to memory");
+        let mut copy = self.headless_copy();
+        (0..frames).for_each(|frame| {
+            if frame > 0 {
+                copy.evolve();
+            }
+            let mut gif_frame = copy.image_of(rows.clone(), cols.clone(), scale).to_gif_frame();
+            // GIF delays are in hundredths of a second
+            gif_frame.delay = (delay / 10).min(u16::MAX as u32) as u16;
+            encoder
+                .write_frame(&gif_frame)
+                .expect("GIF frame should be written to memory");
+        });
+        drop(encoder);
+        gif
+    }
+    pub fn thread_count(&self) -> u32 {
+        self.thread_count
+    }
+    /// At most one band per row; natively the bands share the threads the machine can run.
+    pub fn set_thread_count(&mut self, thread_count: u32) {
+        self.thread_count = thread_count.clamp(1, self.cell_count.max(1));
     }
     pub fn rotate_clockwise(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            self.rotate_selection(rows, cols, true);
+            return;
+        }
+        self.history.begin();
         let mut rotated: Vec<Cell> = vec![];
 
-        let lower_bounds = self.lower_bounds();
-        let upper_bounds = self.upper_bounds();
+        let (lower_bounds, upper_bounds) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
 
         let height = upper_bounds.row() - lower_bounds.row() + 1;
         let width = upper_bounds.col() - lower_bounds.col() + 1;
@@ -100,12 +767,20 @@ impl LifeGrid {
             })
         });
         self.set_populated_cells(rotated);
+        self.history.end();
     }
     pub fn rotate_counter_clockwise(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            self.rotate_selection(rows, cols, false);
+            return;
+        }
+        self.history.begin();
         let mut rotated: Vec<Cell> = vec![];
 
-        let lower_bounds = self.lower_bounds();
-        let upper_bounds = self.upper_bounds();
+        let (lower_bounds, upper_bounds) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
 
         let height = upper_bounds.row() - lower_bounds.row() + 1;
         let width = upper_bounds.col() - lower_bounds.col() + 1;
@@ -122,12 +797,21 @@ impl LifeGrid {
             })
         });
         self.set_populated_cells(rotated);
+        self.history.end();
     }
     pub fn flip_horizontal(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            let (top, bottom) = (rows.start as i64, rows.end as i64 - 1);
+            self.transform_selection(rows, cols, |row, col| (top + bottom - row, col));
+            return;
+        }
+        self.history.begin();
         let mut flipped: Vec<Cell> = vec![];
         
-        let lower_bounds = self.lower_bounds();
-        let upper_bounds = self.upper_bounds();
+        let (lower_bounds, upper_bounds) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
         
         let max_row_index = upper_bounds.row() - lower_bounds.row();
         
@@ -139,12 +823,21 @@ impl LifeGrid {
             })
         });
         self.set_populated_cells(flipped);
+        self.history.end();
     }
     pub fn flip_vertical(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            let (left, right) = (cols.start as i64, cols.end as i64 - 1);
+            self.transform_selection(rows, cols, |row, col| (row, left + right - col));
+            return;
+        }
+        self.history.begin();
         let mut flipped: Vec<Cell> = vec![];
 
-        let lower_bounds = self.lower_bounds();
-        let upper_bounds = self.upper_bounds();
+        let (lower_bounds, upper_bounds) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return,
+        };
 
         let max_col_index = upper_bounds.col() - lower_bounds.col();
 
@@ -156,8 +849,14 @@ impl LifeGrid {
             })
         });
         self.set_populated_cells(flipped);
+        self.history.end();
     }
     pub fn shift_up(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            self.shift_selection(rows, cols, -1, 0);
+            return;
+        }
+        self.history.begin();
         let max_row = self.cell_count - 1;
         
         // Remember the top row states
@@ -175,8 +874,14 @@ impl LifeGrid {
         (0..top_row.len()).for_each(|col| {
             self.set_cell_state(max_row, col as u32, top_row[col]);
         });
+        self.history.end();
     }
     pub fn shift_down(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            self.shift_selection(rows, cols, 1, 0);
+            return;
+        }
+        self.history.begin();
         let max_row = self.cell_count - 1;
 
         // Remember the bottom row states
@@ -193,9 +898,15 @@ impl LifeGrid {
         // Put the bottom row states into the top row
         (0..bottom_row.len()).for_each(|col| {
             self.set_cell_state(0, col as u32, bottom_row[col]);
-        })
+        });
+        self.history.end();
     }
     pub fn shift_left(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            self.shift_selection(rows, cols, 0, -1);
+            return;
+        }
+        self.history.begin();
         let max_col = self.cell_count - 1;
         
         // Remember the left column states
@@ -212,9 +923,15 @@ impl LifeGrid {
         // Put the left states into the right row
         (0..left_column.len()).for_each(|row| {
             self.set_cell_state(row as u32, max_col, left_column[row]);
-        })
+        });
+        self.history.end();
     }
     pub fn shift_right(&mut self) {
+        if let Some((rows, cols)) = self.selected_cells() {
+            self.shift_selection(rows, cols, 0, 1);
+            return;
+        }
+        self.history.begin();
         let max_col = self.cell_count - 1;
         
         // Remember the right column states
@@ -231,50 +948,480 @@ impl LifeGrid {
         // Put the right states into the left row
         (0..right_column.len()).for_each(|row| {
             self.set_cell_state(row as u32, 0, right_column[row]);
-        })
+        });
+        self.history.end();
     }
 }
 impl LifeGrid {
+    /// A grid drawn by `renderer`, e.g. a `FrameBuffer` when running natively.
+    pub fn with_renderer(grid_size: u32, cell_count: u32, renderer: Box<dyn Renderer>) -> Self {
+        Self::create(grid_size, cell_count, Some(renderer))
+    }
+    /// Shows an overview of the whole grid, `size` pixels square, on `renderer`.
+    pub fn set_minimap_renderer(&mut self, mut renderer: Box<dyn Renderer>, size: u32) {
+        self.minimap_size = size as f64;
+        renderer.resize(self.minimap_size);
+        self.minimap = Some(renderer);
+        self.minimap_stale = true;
+    }
+    /// Paints the whole grid with `renderer`.
+    pub fn draw_to(&self, renderer: &mut dyn Renderer) {
+        self.paint(renderer, None);
+    }
+    fn create(grid_size: u32, cell_count: u32, renderer: Option<Box<dyn Renderer>>) -> Self {
+        crate::utils::set_panic_hook();
+        let cell_states = (0..cell_count * cell_count)
+            .map(|_i| CellState::Vacant)
+            .collect();
+        let mut life_grid = Self {
+            cell_states,
+            generation: 0,
+            changed_at: vec![Self::faded_generation(0); (cell_count * cell_count) as usize],
+            recent_changes: VecDeque::from(vec![vec![]]),
+            heat: vec![0; (cell_count * cell_count) as usize],
+            heat_max: 0,
+            heat_metric: HeatMetric::Alive,
+            alive_since: vec![0; (cell_count * cell_count) as usize],
+            live_heat: BTreeMap::new(),
+            cell_count,
+            grid_size: grid_size as f64,
+            thread_count: 1,
+            engine: Box::new(NaiveEngine),
+            engine_kind: EngineKind::Naive,
+            changed_cells: vec![],
+            redraw_all: true,
+            theme: Theme::default(),
+            age_coloring: false,
+            overlay: OverlayKind::None,
+            grid_lines: true,
+            major_grid_line_spacing: 0,
+            cell_shape: CellShape::Square,
+            cell_gap: 0.0,
+            viewport: Viewport::default(),
+            population: 0,
+            row_populations: vec![0; cell_count as usize],
+            col_populations: vec![0; cell_count as usize],
+            births: 0,
+            deaths: 0,
+            renderer,
+            image: None,
+            minimap: None,
+            minimap_size: 0.0,
+            minimap_stale: true,
+            selection: None,
+            clipboard: None,
+            history: History::new(Constants::HISTORY_BUDGET),
+        };
+        life_grid.refresh_pixel_ratio();
+        life_grid
+    }
     fn make_odd(n: u32) -> u32 {
-        if n % 2 == 0 { n + 1 } else { n }
+        if n.is_multiple_of(2) { n + 1 } else { n }
     }
-    fn lower_bounds(&self) -> Cell {
-        let mut low_row = u32::MAX;
-        let mut low_col = u32::MAX;
-        (0..self.cell_count).for_each(|row| {
-            (0..self.cell_count).for_each(|col| {
-                if self.cell_state(row, col) == CellState::Populated {
-                    if row < low_row { low_row = row; }
-                    if col < low_col { low_col = col; }
-                }
+    /// The corners of the smallest rectangle containing every populated cell, if there are any.
+    fn bounds(&self) -> Option<(Cell, Cell)> {
+        Some((self.bounding_box_min()?, self.bounding_box_max()?))
+    }
+    fn active_grid_size(&self) -> f64 {
+        self.grid_size - Constants::BORDER_WIDTH - Constants::BORDER_WIDTH
+    }
+    fn cell_size(&self) -> f64 {
+        self.active_grid_size() / self.cell_count as f64
+    }
+    /// Converts a point in client coordinates to grid pixels from the border's top left.
+    fn point_from_client(&self, client_x: f64, client_y: f64) -> (f64, f64) {
+        self.renderer
+            .as_ref()
+            .map_or((client_x, client_y), |renderer| {
+                renderer.point_from_client(client_x, client_y)
+            })
+    }
+    fn zoomed_cell_size(&self) -> f64 {
+        self.cell_size() * self.viewport.zoom()
+    }
+    /// The rows and columns of the cells that are at least partly in view.
+    fn visible_cells(&self) -> (Range<u32>, Range<u32>) {
+        let cell_size = self.zoomed_cell_size();
+        let visible = |offset: f64| {
+            let first = (offset / cell_size).floor() as u32;
+            let last = ((offset + self.active_grid_size()) / cell_size).ceil() as u32;
+            first.min(self.cell_count)..last.min(self.cell_count)
+        };
+        (
+            visible(self.viewport.offset_y()),
+            visible(self.viewport.offset_x()),
+        )
+    }
+    /// Rotates the cells in `rows` and `cols` a quarter turn about their centre, and selects the
+    /// rectangle they turn into. Nothing changes if that rectangle would go past the edge of the
+    /// grid or cover populated cells outside the selection.
+    fn rotate_selection(&mut self, rows: Range<u32>, cols: Range<u32>, clockwise: bool) {
+        let (top, height) = (rows.start as i64, rows.len() as i64);
+        let (left, width) = (cols.start as i64, cols.len() as i64);
+        // Centred when the height and width are both odd or both even. Otherwise the rectangle
+        // can't stay centred, and rounds so that turning it back returns it to where it was.
+        let difference = height - width;
+        let half = |value: i64| {
+            if difference > 0 {
+                value.div_euclid(2)
+            } else {
+                -(-value).div_euclid(2)
+            }
+        };
+        let (new_top, new_left) = (top + half(difference), left + half(-difference));
+        let cell_count = self.cell_count as i64;
+        let in_grid = |start: i64, len: i64| start >= 0 && start + len <= cell_count;
+        if !in_grid(new_top, width) || !in_grid(new_left, height) {
+            return;
+        }
+        let new_rows = new_top as u32..(new_top + width) as u32;
+        let new_cols = new_left as u32..(new_left + height) as u32;
+        let covers_outside_cells = new_rows.clone().any(|row| {
+            new_cols.clone().any(|col| {
+                !(rows.contains(&row) && cols.contains(&col))
+                    && self.cell_state(row, col) == CellState::Populated
             })
         });
-        Cell::new(low_row, low_col)
+        if covers_outside_cells {
+            return;
+        }
+
+        self.history.begin();
+        self.transform_selection(rows, cols, |row, col| {
+            let (row, col) = (row - top, col - left);
+            let (row, col) = if clockwise {
+                (col, height - 1 - row)
+            } else {
+                (width - 1 - col, row)
+            };
+            (new_top + row, new_left + col)
+        });
+        self.select(
+            &Cell::new(new_rows.start, new_cols.start),
+            &Cell::new(new_rows.end - 1, new_cols.end - 1),
+        );
+        self.history.end();
     }
-    fn upper_bounds(&self) -> Cell {
-        let mut high_row = 0;
-        let mut high_col = 0;
-        (0..self.cell_count).for_each(|row| {
-            (0..self.cell_count).for_each(|col| {
-                if self.cell_state(row, col) == CellState::Populated {
-                    if row > high_row { high_row = row; }
-                    if col > high_col { high_col = col; }
+    /// Moves the populated cells in `rows` and `cols` by `row_offset` and `col_offset`, with
+    /// those that go past one side coming back in the other, like the whole grid's shifts.
+    fn shift_selection(
+        &mut self,
+        rows: Range<u32>,
+        cols: Range<u32>,
+        row_offset: i64,
+        col_offset: i64,
+    ) {
+        let (top, height) = (rows.start as i64, rows.len() as i64);
+        let (left, width) = (cols.start as i64, cols.len() as i64);
+        self.transform_selection(rows, cols, |row, col| {
+            (
+                top + (row - top + row_offset).rem_euclid(height),
+                left + (col - left + col_offset).rem_euclid(width),
+            )
+        });
+    }
+    /// Moves each populated cell in `rows` and `cols` to the row and column `map` gives for
+    /// it, leaving the rest of the grid as it is. Cells moved past the edge of the grid are
+    /// lost.
+    fn transform_selection(
+        &mut self,
+        rows: Range<u32>,
+        cols: Range<u32>,
+        map: impl Fn(i64, i64) -> (i64, i64),
+    ) {
+        let populated: Vec<(u32, u32)> = rows
+            .clone()
+            .flat_map(|row| cols.clone().map(move |col| (row, col)))
+            .filter(|(row, col)| self.cell_state(*row, *col) == CellState::Populated)
+            .collect();
+        self.history.begin();
+        populated
+            .iter()
+            .for_each(|(row, col)| self.set_cell_state(*row, *col, CellState::Vacant));
+        populated.into_iter().for_each(|(row, col)| {
+            let (row, col) = map(row as i64, col as i64);
+            let cell_count = self.cell_count as i64;
+            if (0..cell_count).contains(&row) && (0..cell_count).contains(&col) {
+                self.set_cell_state(row as u32, col as u32, CellState::Populated);
+            }
+        });
+        self.history.end();
+    }
+    /// The number of rows and columns from the top left of `pattern` to its furthest cells.
+    fn pattern_size(pattern: &Pattern) -> (u32, u32) {
+        let cells = pattern.cells();
+        let height = cells.iter().map(|cell| cell.row() + 1).max().unwrap_or(0);
+        let width = cells.iter().map(|cell| cell.col() + 1).max().unwrap_or(0);
+        (height, width)
+    }
+    fn point(cell: &Cell) -> (i32, i32) {
+        let coordinate = |value: u32| value.min(i32::MAX as u32) as i32;
+        (coordinate(cell.row()), coordinate(cell.col()))
+    }
+    /// Undoes or redoes `action`, and returns the action that does the opposite.
+    fn apply(&mut self, action: Action, undo: bool) -> Action {
+        match action {
+            Action::Edit(changes) => {
+                if undo {
+                    changes
+                        .iter()
+                        .rev()
+                        .for_each(|(index, old_state, _)| self.set_state_at(*index, *old_state));
+                } else {
+                    changes
+                        .iter()
+                        .for_each(|(index, _, new_state)| self.set_state_at(*index, *new_state));
                 }
-            })
+                Action::Edit(changes)
+            }
+            Action::Snapshot(states) => {
+                let current = self.cell_states.clone();
+                states
+                    .iter()
+                    .enumerate()
+                    .for_each(|(index, state)| self.set_state_at(index, *state));
+                Action::Snapshot(current)
+            }
+        }
+    }
+    /// Sets the cells that are in the grid to `state`.
+    fn set_states(&mut self, cells: Vec<(i32, i32)>, state: CellState) {
+        self.history.begin();
+        cells.into_iter().for_each(|(row, col)| {
+            if self.in_grid(row, col) {
+                self.set_cell_state(row as u32, col as u32, state);
+            }
         });
-        Cell::new(high_row, high_col)
+        self.history.end();
     }
-    fn active_grid_size(&self) -> f64 {
-        self.grid_size - Constants::BORDER_WIDTH - Constants::BORDER_WIDTH
+    /// The rows and columns of the selection, if there is one.
+    fn selected_cells(&self) -> Option<(Range<u32>, Range<u32>)> {
+        self.selection
+            .as_ref()
+            .map(|(min, max)| self.region(min, max))
     }
-    fn cell_size(&self) -> f64 {
-        self.active_grid_size() / self.cell_count as f64
+    /// The rows and columns to export: the whole grid, or the bounding box if `crop` is set
+    /// and any cell is populated.
+    fn export_region(&self, crop: bool) -> (Range<u32>, Range<u32>) {
+        match (crop, self.bounding_box_min(), self.bounding_box_max()) {
+            (true, Some(min), Some(max)) => self.region(&min, &max),
+            _ => (0..self.cell_count, 0..self.cell_count),
+        }
+    }
+    /// The rows and columns containing every cell that is populated in the next `frames`
+    /// generations, starting with the current one, or the whole grid if none are.
+    fn run_bounds(&self, frames: u32) -> (Range<u32>, Range<u32>) {
+        let mut copy = self.headless_copy();
+        let mut bounds: Option<(Cell, Cell)> = None;
+        (0..frames).for_each(|frame| {
+            if frame > 0 {
+                copy.evolve();
+            }
+            if let (Some(min), Some(max)) = (copy.bounding_box_min(), copy.bounding_box_max()) {
+                bounds = Some(match bounds.take() {
+                    None => (min, max),
+                    Some((union_min, union_max)) => (
+                        Cell::new(union_min.row().min(min.row()), union_min.col().min(min.col())),
+                        Cell::new(union_max.row().max(max.row()), union_max.col().max(max.col())),
+                    ),
+                });
+            }
+        });
+        match bounds {
+            Some((min, max)) => self.region(&min, &max),
+            None => self.export_region(false),
+        }
+    }
+    /// A copy of the grid that isn't drawn anywhere, for running ahead without changing it.
+    fn headless_copy(&self) -> Self {
+        let mut copy = Self::create(self.grid_size as u32, self.cell_count, None);
+        // Nothing run on the copy is undone
+        copy.history.set_budget(0);
+        copy.cell_states = self.cell_states.clone();
+        copy.generation = self.generation;
+        copy.changed_at = self.changed_at.clone();
+        copy.recent_changes = self.recent_changes.clone();
+        copy.heat = self.heat.clone();
+        copy.heat_max = self.heat_max;
+        copy.heat_metric = self.heat_metric;
+        copy.alive_since = self.alive_since.clone();
+        copy.live_heat = self.live_heat.clone();
+        copy.population = self.population;
+        copy.row_populations = self.row_populations.clone();
+        copy.col_populations = self.col_populations.clone();
+        copy.thread_count = self.thread_count;
+        copy.theme = self.theme.clone();
+        copy.age_coloring = self.age_coloring;
+        copy.overlay = self.overlay;
+        copy.grid_lines = self.grid_lines;
+        copy.major_grid_line_spacing = self.major_grid_line_spacing;
+        copy.cell_shape = self.cell_shape;
+        copy.cell_gap = self.cell_gap;
+        copy.set_engine(self.engine_kind);
+        copy
+    }
+    /// The rows and columns between the corners `min` and `max` inclusive, limited to the
+    /// grid.
+    fn region(&self, min: &Cell, max: &Cell) -> (Range<u32>, Range<u32>) {
+        let range = |from: u32, to: u32| {
+            from.min(to).min(self.cell_count)..from.max(to).saturating_add(1).min(self.cell_count)
+        };
+        (range(min.row(), max.row()), range(min.col(), max.col()))
     }
     fn cell_index(&self, row: u32, col: u32) -> usize {
         (row * self.cell_count + col) as usize
     }
+    /// The state to store for `state`. Only cells outside the grid are invalid, and the evolve
+    /// kernels count every stored state as a 0 or 1 byte, so invalid is stored as vacant.
+    fn stored_state(state: CellState) -> CellState {
+        if state == CellState::Invalid {
+            CellState::Vacant
+        } else {
+            state
+        }
+    }
+    fn set_state_at(&mut self, index: usize, state: CellState) {
+        let state = Self::stored_state(state);
+        if self.cell_states[index] != state {
+            self.history.record(index, self.cell_states[index], state);
+            self.record_change(index, state);
+            self.cell_states[index] = state;
+            self.engine.cell_edited(index, state);
+        }
+    }
+    /// Updates the statistics and the cells to draw for a cell that is about to change to
+    /// `state`.
+    fn record_change(&mut self, index: usize, state: CellState) {
+        let row = index / self.cell_count as usize;
+        let col = index % self.cell_count as usize;
+        if state == CellState::Populated {
+            self.population += 1;
+            self.row_populations[row] += 1;
+            self.col_populations[col] += 1;
+        } else if self.cell_states[index] == CellState::Populated {
+            self.population -= 1;
+            self.row_populations[row] -= 1;
+            self.col_populations[col] -= 1;
+        }
+        if self.heat_metric == HeatMetric::Alive {
+            if state == CellState::Populated {
+                self.alive_since[index] = self.generation;
+                *self.live_heat.entry(self.live_heat_key(index)).or_insert(0) += 1;
+            } else if self.cell_states[index] == CellState::Populated {
+                let key = self.live_heat_key(index);
+                if let Some(count) = self.live_heat.get_mut(&key) {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.live_heat.remove(&key);
+                    }
+                }
+                self.add_heat(index, self.generation.wrapping_sub(self.alive_since[index]));
+            }
+        }
+        self.changed_at[index] = self.generation;
+        if let Some(changes) = self.recent_changes.back_mut() {
+            changes.push(index);
+        }
+        self.mark_changed(index);
+    }
+    /// The number of generations after changing in which a cell's colour may still change, by
+    /// ageing or by its trail fading.
+    const FADING_GENERATIONS: u32 = {
+        let trail = (u8::MAX as u32).div_ceil(Constants::TRAIL_FADE as u32);
+        if trail > Theme::ANCIENT_AGE {
+            trail
+        } else {
+            Theme::ANCIENT_AGE
+        }
+    };
+    /// A `changed_at` for vacant cells at `generation` whose trail, if any, has faded.
+    fn faded_generation(generation: u32) -> u32 {
+        generation.wrapping_sub(Self::FADING_GENERATIONS)
+    }
+    /// The trail of the vacant cell at `index`, which fades from 255 to 0 after it is vacated.
+    fn trail(&self, index: usize) -> u8 {
+        let generations = self.generation.wrapping_sub(self.changed_at[index]);
+        let fade = generations.saturating_mul(Constants::TRAIL_FADE as u32);
+        u8::MAX.saturating_sub(fade.min(u8::MAX as u32) as u8)
+    }
+    /// Marks the cells that changed in earlier generations whose colour has changed with the
+    /// last `evolve`, as they age or their trails fade.
+    fn mark_fading_cells(&mut self) {
+        let trail = self.overlay == OverlayKind::Trail;
+        if !self.age_coloring && !trail {
+            return;
+        }
+        let count: usize = self.recent_changes.iter().map(Vec::len).sum();
+        if count > self.cell_states.len() {
+            // Cheaper to repaint everything than to work through them
+            self.invalidate();
+            return;
+        }
+        let newest = self.recent_changes.len() - 1;
+        let (changed_at, cell_states) = (&self.changed_at, &self.cell_states);
+        let age_coloring = self.age_coloring;
+        let fading: Vec<usize> = (0..newest)
+            .flat_map(|position| {
+                let generations = (newest - position) as u32;
+                let generation = self.generation.wrapping_sub(generations);
+                self.recent_changes[position]
+                    .iter()
+                    .copied()
+                    .filter(move |index| changed_at[*index] == generation)
+                    .filter(move |index| match cell_states[*index] {
+                        CellState::Populated => age_coloring && generations <= Theme::ANCIENT_AGE,
+                        _ => {
+                            trail
+                                && (generations - 1) * (Constants::TRAIL_FADE as u32)
+                                    < u8::MAX as u32
+                        }
+                    })
+            })
+            .collect();
+        fading.into_iter().for_each(|index| self.mark_changed(index));
+    }
+    fn mark_changed(&mut self, index: usize) {
+        self.minimap_stale = true;
+        if !self.redraw_all {
+            self.changed_cells.push(index);
+            // Past this point a full redraw is cheaper than keeping track
+            if self.changed_cells.len() > self.cell_states.len() {
+                self.invalidate();
+            }
+        }
+    }
+    fn add_heat(&mut self, index: usize, heat: u32) {
+        self.heat[index] = self.heat[index].saturating_add(heat);
+        self.heat_max = self.heat_max.max(self.heat[index]);
+    }
+    /// The activity count of the cell at `index`.
+    fn cell_heat(&self, index: usize) -> u32 {
+        if self.heat_metric == HeatMetric::Alive
+            && self.cell_states[index] == CellState::Populated
+        {
+            self.heat[index]
+                .saturating_add(self.generation.wrapping_sub(self.alive_since[index]))
+        } else {
+            self.heat[index]
+        }
+    }
+    /// The highest activity count of any cell.
+    fn current_heat_max(&self) -> u32 {
+        self.live_heat
+            .keys()
+            .next_back()
+            .map_or(self.heat_max, |key| self.heat_max.max((key + self.generation as i64) as u32))
+    }
+    fn live_heat_key(&self, index: usize) -> i64 {
+        self.heat[index] as i64 - self.alive_since[index] as i64
+    }
+    fn vacate_cells(&mut self) {
+        (0..self.cell_states.len()).for_each(|index| {
+            self.set_state_at(index, CellState::Vacant);
+        })
+    }
     fn set_populated_cells(&mut self, populated: Vec<Cell>) {
-        self.vacate_all_cells();
+        self.vacate_cells();
         populated.into_iter().for_each(|cell| {
             self.set_cell_state(cell.row(), cell.col(), CellState::Populated);
         })
@@ -285,96 +1432,352 @@ impl LifeGrid {
     fn in_grid(&self, row: i32, col: i32) -> bool {
         self.in_bounds(row) && self.in_bounds(col)
     }
-    fn is_populated(&self, row: i32, col: i32) -> bool {
-        self.in_grid(row, col) && self.cell_state(row as u32, col as u32) == CellState::Populated
-    }
-    fn count_neighbors(&self, row: u32, col: u32) -> usize {
-        let mut neighbors = 0;
-        (row as i32 - 1..=row as i32 + 1).for_each(|neighbor_row| {
-            (col as i32 - 1..=col as i32 + 1).for_each(|neighbor_col| {
-                // Don't compare to same cell
-                if !(neighbor_col == col as i32 && neighbor_row == row as i32)
-                    && self.is_populated(neighbor_row, neighbor_col)
-                {
-                    neighbors += 1;
-                }
-            })
+    /// Paints the minimap into an image and copies it to `renderer`, or paints it directly if
+    /// the renderer can't copy images.
+    fn draw_minimap(&self, renderer: &mut dyn Renderer) {
+        if renderer.can_blit() {
+            let pixels = (self.minimap_size * renderer.pixel_ratio()).round() as u32;
+            let mut image = FrameBuffer::new(pixels, pixels, pixels as f64 / self.minimap_size);
+            self.paint_minimap(&mut image);
+            renderer.blit(&image);
+        } else {
+            self.paint_minimap(renderer);
+        }
+    }
+    /// Paints the whole grid in blocks of cells no smaller than a device pixel, each shaded by
+    /// how many of its cells are populated, with an outline around the part in view.
+    fn paint_minimap(&self, renderer: &mut dyn Renderer) {
+        let cell_count = self.cell_count as usize;
+        let blocks = ((self.minimap_size * renderer.pixel_ratio()).round() as usize)
+            .clamp(1, cell_count.max(1));
+        let mut counts = vec![0u32; blocks * blocks];
+        self.cell_states
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state == CellState::Populated)
+            .for_each(|(index, _)| {
+                let block_row = index / cell_count * blocks / cell_count;
+                let block_col = index % cell_count * blocks / cell_count;
+                counts[block_row * blocks + block_col] += 1;
+            });
+
+        let cells_per_block = (cell_count as f64 / blocks as f64).powi(2);
+        let block_size = self.minimap_size / blocks as f64;
+        let vacant = self.theme.cell_color(CellState::Vacant);
+        let populated = self.theme.cell_color(CellState::Populated);
+        counts.iter().enumerate().for_each(|(block, count)| {
+            // The square root keeps sparse blocks visible
+            let density = (*count as f64 / cells_per_block).min(1.0).sqrt();
+            renderer.fill_rect(
+                (block % blocks) as f64 * block_size,
+                (block / blocks) as f64 * block_size,
+                block_size,
+                block_size,
+                vacant.interpolate(populated, density),
+            );
         });
-        neighbors
-    }
-    fn get_canvas_element(canvas_id: &str) -> web_sys::HtmlCanvasElement {
-        let window = web_sys::window().expect("should be a global `window`");
-        let document = window.document().expect("window should have a document");
-        let element = document
-            .get_element_by_id(canvas_id)
-            .expect(format!("document should have an element with ID '{}'", canvas_id).as_str());
-        element
-            .dyn_into::<web_sys::HtmlCanvasElement>()
-            .map_err(|_| ())
-            .expect(
-                format!(
-                    "element with ID '{}' should be an HTML canvas element",
-                    canvas_id
-                )
-                .as_str(),
-            )
+
+        // Outline the view, inside the edges of the minimap
+        let scale = self.minimap_size / (self.active_grid_size() * self.viewport.zoom());
+        let inset = Constants::OUTLINE_WIDTH / 2.0;
+        let size = self.minimap_size / self.viewport.zoom() - Constants::OUTLINE_WIDTH;
+        renderer.stroke_rect(
+            self.viewport.offset_x() * scale + inset,
+            self.viewport.offset_y() * scale + inset,
+            size,
+            size,
+            Constants::OUTLINE_WIDTH,
+            self.theme.highlight(),
+        );
+    }
+    /// Paints the changes into the image from the last draw, or a new image if everything
+    /// needs repainting, and copies it to `renderer`.
+    fn draw_image(&mut self, renderer: &mut dyn Renderer) {
+        let pixels = (self.grid_size * renderer.pixel_ratio()).round() as u32;
+        let image = match self.image.take() {
+            Some(mut image) if !self.redraw_all && image.width() == pixels => {
+                self.paint(&mut image, Some(&self.changed_cells));
+                image
+            }
+            _ => {
+                let mut image = FrameBuffer::new(pixels, pixels, pixels as f64 / self.grid_size);
+                self.paint(&mut image, None);
+                image
+            }
+        };
+        renderer.blit(&image);
+        self.image = Some(image);
     }
-    fn get_canvas_rendering_context_2d(
-        canvas_element: &web_sys::HtmlCanvasElement,
-    ) -> web_sys::CanvasRenderingContext2d {
-        canvas_element
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .expect("HTML canvas element should have a 2D context")
-    }
-    fn draw_grid(&self) {
-        self.context.save();
+    /// Paints the border and every visible cell, or only the visible cells in
+    /// `changed_cells` if it is given.
+    fn paint(&self, renderer: &mut dyn Renderer, changed_cells: Option<&[usize]>) {
+        if changed_cells.is_none() {
+            self.paint_border(renderer);
+        }
 
+        // Keep the cells inside the border
+        renderer.clip(
+            Constants::BORDER_WIDTH,
+            Constants::BORDER_WIDTH,
+            self.active_grid_size(),
+            self.active_grid_size(),
+        );
+
+        // Draw the cells
+        let (rows, cols) = self.visible_cells();
+        let indexes: Vec<usize> = match changed_cells {
+            None => rows
+                .flat_map(|row| cols.clone().map(move |col| (row, col)))
+                .map(|(row, col)| self.cell_index(row, col))
+                .collect(),
+            Some(changed_cells) => changed_cells
+                .iter()
+                .filter(|index| {
+                    let index = **index as u32;
+                    rows.contains(&(index / self.cell_count))
+                        && cols.contains(&(index % self.cell_count))
+                })
+                .copied()
+                .collect(),
+        };
+        self.paint_cells(renderer, &indexes);
+
+        // Outline the selection, which repainted cells may have drawn over
+        if let Some((rows, cols)) = self.selected_cells() {
+            let cell_size = self.zoomed_cell_size();
+            renderer.stroke_rect(
+                Constants::BORDER_WIDTH + cols.start as f64 * cell_size - self.viewport.offset_x(),
+                Constants::BORDER_WIDTH + rows.start as f64 * cell_size - self.viewport.offset_y(),
+                cols.len() as f64 * cell_size,
+                rows.len() as f64 * cell_size,
+                Constants::OUTLINE_WIDTH,
+                self.theme.highlight(),
+            );
+        }
+        renderer.unclip();
+    }
+    fn paint_border(&self, renderer: &mut dyn Renderer) {
         // Draw the outer border
-        self.context.set_fill_style_str(Constants::BORDER_COLOR);
-        self.context
-            .fill_rect(0.0, 0.0, self.grid_size, self.grid_size);
+        renderer.fill_rect(0.0, 0.0, self.grid_size, self.grid_size, self.theme.border());
 
         // Draw the inner border
-        self.context
-            .set_fill_style_str(Constants::BORDER_HIGHLIGHT_COLOR);
         let inset = (Constants::BORDER_WIDTH / 4.0) * 3.0;
-        self.context.fill_rect(
+        renderer.fill_rect(
             inset,
             inset,
             self.grid_size - inset - inset,
             self.grid_size - inset - inset,
+            self.theme.border_highlight(),
         );
-
-        // Draw the cells
-        self.context
-            .set_stroke_style_str(Constants::CELL_BORDER_COLOR);
-        self.context.set_stroke_style_str("black");
-        self.context.set_line_width(0.25);
-        self.cell_states
+    }
+    /// Fills the cells at `indexes`, then strokes their edges clipped to those cells, so lines
+    /// shared with cells that aren't repainted keep the stroke they already have.
+    fn paint_cells(&self, renderer: &mut dyn Renderer, indexes: &[usize]) {
+        let cell_size = self.zoomed_cell_size();
+        let corners: Vec<(f64, f64)> = indexes
             .iter()
-            .enumerate()
-            .for_each(|(index, cell_state)| {
-                // Get the color of the cell, based on its status
-                let color = match cell_state {
-                    CellState::Populated => Constants::POPULATED_CELL_COLOR,
-                    _ => Constants::VACANT_CELL_COLOR,
-                };
-                self.context.set_fill_style_str(color);
+            .map(|index| self.cell_corner(*index))
+            .collect();
+        self.paint_cells_at(renderer, indexes, &corners, cell_size);
+    }
+    /// Paints the cells at `indexes` with their top left corners at `corners`.
+    fn paint_cells_at(
+        &self,
+        renderer: &mut dyn Renderer,
+        indexes: &[usize],
+        corners: &[(f64, f64)],
+        cell_size: f64,
+    ) {
+        // Draw the cells
+        if self.cell_shape == CellShape::Square && self.cell_gap == 0.0 {
+            // Cells of the same colour are filled together
+            let mut fills: Vec<(Color, Vec<(f64, f64)>)> = vec![];
+            indexes.iter().zip(corners).for_each(|(index, corner)| {
+                let color = self.cell_color(*index);
+                match fills.iter_mut().find(|(fill_color, _)| *fill_color == color) {
+                    Some((_, corners)) => corners.push(*corner),
+                    None => fills.push((color, vec![*corner])),
+                }
+            });
+            fills
+                .iter()
+                .for_each(|(color, corners)| renderer.fill_cells(corners, cell_size, *color));
+        } else {
+            // On a vacant background, as the shapes don't cover it
+            let vacant = self.theme.cell_color(CellState::Vacant);
+            renderer.fill_cells(corners, cell_size, vacant);
+            indexes.iter().zip(corners).for_each(|(index, (x, y))| {
+                self.paint_shape(renderer, *x, *y, cell_size, self.cell_color(*index))
+            });
+        }
 
-                // Calculate the top left corner of the cell
-                let cell_size = self.cell_size();
-                let x =
-                    Constants::BORDER_WIDTH + (index % self.cell_count as usize) as f64 * cell_size;
-                let y =
-                    Constants::BORDER_WIDTH + (index / self.cell_count as usize) as f64 * cell_size;
+        // Draw the grid lines around the cells
+        if !self.grid_lines {
+            return;
+        }
+        let device_cell_size = cell_size * renderer.pixel_ratio();
+        if device_cell_size >= Constants::MIN_GRID_LINE_SPACING {
+            renderer.stroke_cells(
+                corners,
+                cell_size,
+                Constants::GRID_LINE_WIDTH,
+                self.theme.grid_line(),
+            );
+        }
+        let spacing = self.major_grid_line_spacing;
+        if spacing > 0 && device_cell_size * spacing as f64 >= Constants::MIN_GRID_LINE_SPACING {
+            indexes.iter().zip(corners).for_each(|(index, (x, y))| {
+                self.paint_major_grid_lines(renderer, *index, *x, *y, cell_size)
+            });
+        }
+    }
+    fn text(&self, rows: Range<u32>, cols: Range<u32>, mode: TextMode) -> String {
+        TextRenderer::new(mode).render(rows, cols, |row, col| {
+            self.cell_states[self.cell_index(row, col)] == CellState::Populated
+        })
+    }
+    /// The pixels across each cell of an image of `rows` and `cols`: `scale`, reduced if the
+    /// image would be wider or taller than `Constants::MAX_IMAGE_SIZE`, and at least one.
+    fn image_scale(rows: &Range<u32>, cols: &Range<u32>, scale: u32) -> u32 {
+        let cells_across = rows.len().max(cols.len()).max(1) as u32;
+        scale.min(Constants::MAX_IMAGE_SIZE / cells_across).max(1)
+    }
+    /// Paints the cells in `rows` and `cols` into a new image, `image_scale` pixels across each
+    /// and at least one pixel in size.
+    fn image_of(&self, rows: Range<u32>, cols: Range<u32>, scale: u32) -> FrameBuffer {
+        let scale = Self::image_scale(&rows, &cols, scale);
+        let width = (cols.len() as u32 * scale).max(1);
+        let height = (rows.len() as u32 * scale).max(1);
+        let mut image = FrameBuffer::new(width, height, 1.0);
+        let mut indexes = vec![];
+        let mut corners = vec![];
+        rows.clone().for_each(|row| {
+            cols.clone().for_each(|col| {
+                indexes.push(self.cell_index(row, col));
+                corners.push((
+                    ((col - cols.start) * scale) as f64,
+                    ((row - rows.start) * scale) as f64,
+                ));
+            })
+        });
+        self.paint_cells_at(&mut image, &indexes, &corners, scale as f64);
+        image
+    }
+    /// Writes the cells in `rows` and `cols` as an SVG document, with the shapes drawn over a
+    /// vacant background and the grid lines as two paths.
+    fn svg(&self, rows: Range<u32>, cols: Range<u32>) -> String {
+        let cell_size = Constants::SVG_CELL_SIZE;
+        let width = cols.len() as f64 * cell_size;
+        let height = rows.len() as f64 * cell_size;
+        let mut document = SvgDocument::new(width, height);
+        let vacant = self.theme.cell_color(CellState::Vacant);
+        document.fill_rect(0.0, 0.0, width, height, vacant);
+        rows.clone().for_each(|row| {
+            cols.clone().for_each(|col| {
+                let color = self.cell_color(self.cell_index(row, col));
+                if color != vacant {
+                    let x = (col - cols.start) as f64 * cell_size;
+                    let y = (row - rows.start) as f64 * cell_size;
+                    self.paint_shape(&mut document, x, y, cell_size, color);
+                }
+            })
+        });
 
-                // Draw the cell
-                self.context.fill_rect(x, y, cell_size, cell_size);
-                self.context.stroke_rect(x, y, cell_size, cell_size);
+        if self.grid_lines {
+            // Lines between rows, then between columns, with the major lines kept apart
+            let mut minor = String::new();
+            let mut major = String::new();
+            let spacing = self.major_grid_line_spacing;
+            let is_major = |line: u32| spacing > 0 && line.is_multiple_of(spacing);
+            rows.clone().chain(rows.end..rows.end + 1).for_each(|row| {
+                let commands = if is_major(row) { &mut major } else { &mut minor };
+                let y = (row - rows.start) as f64 * cell_size;
+                write!(commands, "M0 {}H{}", y, width).unwrap();
+            });
+            cols.clone().chain(cols.end..cols.end + 1).for_each(|col| {
+                let commands = if is_major(col) { &mut major } else { &mut minor };
+                let x = (col - cols.start) as f64 * cell_size;
+                write!(commands, "M{} 0V{}", x, height).unwrap();
             });
-        self.context.restore();
+            document.path(&minor, Constants::GRID_LINE_WIDTH, self.theme.grid_line());
+            if !major.is_empty() {
+                document.path(&major, Constants::MAJOR_GRID_LINE_WIDTH, self.theme.grid_line());
+            }
+        }
+        document.finish()
     }
-}
+    /// Fills the shape of a cell whose top left corner is at `x`, `y`, inset by the gap.
+    fn paint_shape(
+        &self,
+        renderer: &mut dyn Renderer,
+        x: f64,
+        y: f64,
+        cell_size: f64,
+        color: Color,
+    ) {
+        let gap = self.cell_gap.min(cell_size / 2.0);
+        let size = cell_size - gap;
+        let (x, y) = (x + gap / 2.0, y + gap / 2.0);
+        match self.cell_shape {
+            CellShape::Square => renderer.fill_rect(x, y, size, size, color),
+            CellShape::Rounded => renderer.fill_rounded_rect(x, y, size, size, size / 4.0, color),
+            CellShape::Circle => renderer.fill_rounded_rect(x, y, size, size, size / 2.0, color),
+        }
+    }
+    /// Paints the parts of the major grid lines that lie along the edges of the cell at
+    /// `index`, whose top left corner is at `x`, `y`. Every cell repaints its own parts, so
+    /// the lines survive the cells on either side being repainted.
+    fn paint_major_grid_lines(
+        &self,
+        renderer: &mut dyn Renderer,
+        index: usize,
+        x: f64,
+        y: f64,
+        cell_size: f64,
+    ) {
+        let spacing = self.major_grid_line_spacing as usize;
+        let row = index / self.cell_count as usize;
+        let col = index % self.cell_count as usize;
+        let half = Constants::MAJOR_GRID_LINE_WIDTH / 2.0;
+        let color = self.theme.grid_line();
+        [(row, y), (row + 1, y + cell_size)]
+            .iter()
+            .filter(|(line, _)| line % spacing == 0)
+            .for_each(|(_, line_y)| {
+                renderer.fill_rect(x, line_y - half, cell_size, half * 2.0, color)
+            });
+        [(col, x), (col + 1, x + cell_size)]
+            .iter()
+            .filter(|(line, _)| line % spacing == 0)
+            .for_each(|(_, line_x)| {
+                renderer.fill_rect(line_x - half, y, half * 2.0, cell_size, color)
+            });
+    }
+    /// The colour of a cell, based on its state, age and activity.
+    fn cell_color(&self, index: usize) -> Color {
+        let vacant = self.theme.cell_color(CellState::Vacant);
+        match (self.cell_states[index], self.overlay) {
+            (CellState::Populated, _) if self.age_coloring => {
+                self.theme.age_color(self.generation.wrapping_sub(self.changed_at[index]))
+            }
+            (CellState::Vacant, OverlayKind::Trail) => {
+                let fraction = self.trail(index) as f64 / u8::MAX as f64;
+                vacant.interpolate(self.theme.trail(), fraction)
+            }
+            (CellState::Vacant, OverlayKind::Heatmap) if self.heat_max > 0 => {
+                let fraction = self.heat[index] as f64 / self.current_heat_max() as f64;
+                vacant.interpolate(self.theme.heat(), fraction)
+            }
+            (state, _) => self.theme.cell_color(state),
+        }
+    }
+    /// The top left corner of the cell at `index`.
+    fn cell_corner(&self, index: usize) -> (f64, f64) {
+        let cell_size = self.zoomed_cell_size();
+        let x = Constants::BORDER_WIDTH + (index % self.cell_count as usize) as f64 * cell_size
+            - self.viewport.offset_x();
+        let y = Constants::BORDER_WIDTH + (index / self.cell_count as usize) as f64 * cell_size
+            - self.viewport.offset_y();
+        (x, y)
+    }
+}
\ No newline at end of file
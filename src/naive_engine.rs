@@ -0,0 +1,21 @@
+use crate::cell_state::CellState;
+use crate::engine::Engine;
+use crate::evolution;
+
+/// Computes every cell of every generation, in parallel bands.
+pub(crate) struct NaiveEngine;
+
+impl Engine for NaiveEngine {
+    fn step(
+        &mut self,
+        cells: &[CellState],
+        cell_count: u32,
+        thread_count: u32,
+    ) -> Vec<(usize, CellState)> {
+        let next_generation = evolution::next_generation(cells, cell_count, thread_count);
+        (0..cells.len())
+            .filter(|index| next_generation[*index] != cells[*index])
+            .map(|index| (index, next_generation[index]))
+            .collect()
+    }
+}
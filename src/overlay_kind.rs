@@ -0,0 +1,11 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum OverlayKind {
+    None,
+    // Vacant cells fade from the trail colour after being vacated
+    Trail,
+    // Vacant cells are coloured by their activity count
+    Heatmap,
+}
@@ -2,6 +2,7 @@ use wasm_bindgen::prelude::wasm_bindgen;
 use crate::cell::Cell;
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Pattern {
     metadata: Vec<String>,
     cells: Vec<Cell>,
@@ -9,11 +9,56 @@ pub struct PatternParser;
 
 #[wasm_bindgen]
 impl PatternParser {
+    /// Parses RLE data if it has an RLE header line, or else plaintext (.cells) data.
+    pub fn parse_data(data: &str) -> Pattern {
+        let is_rle = data
+            .lines()
+            .map(|line| line.trim())
+            .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+            .is_some_and(|line| line.starts_with('x') && line.contains('='));
+        if is_rle {
+            Self::parse_rle_data(data)
+        } else {
+            Self::parse_cells_data(data)
+        }
+    }
+    /// Whether `data` looks like an RLE or plaintext (.cells) pattern rather than other text:
+    /// an RLE header followed by at least one run or the closing `!`, or rows of only `.`, `O`
+    /// and `*`. Comment lines are allowed in either.
+    pub fn is_pattern_data(data: &str) -> bool {
+        let mut lines = data
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        match lines.next() {
+            None => false,
+            Some(header) if header.starts_with('x') && header.contains('=') => {
+                let body: String = lines.collect();
+                let runs = body.split('!').next().unwrap_or_default();
+                (body.contains('!') || runs.contains(|ch| "bo$".contains(ch)))
+                    && runs.chars().all(|ch| {
+                        ch.is_ascii_digit() || ch.is_whitespace() || "bo$xyz".contains(ch)
+                    })
+            }
+            Some(first) => {
+                let mut rows = Some(first)
+                    .into_iter()
+                    .chain(lines)
+                    .filter(|line| !line.starts_with('!'))
+                    .peekable();
+                rows.peek().is_some()
+                    && rows.all(|row| {
+                        row.chars()
+                            .all(|ch| ch.is_whitespace() || ".O*".contains(ch))
+                    })
+            }
+        }
+    }
     pub fn parse_cells_data(data: &str) -> Pattern {
         fn parse_line(line: &str, row: i32) -> Vec<Cell> {
             let mut cells: Vec<Cell> = vec![];
             for (col, ch) in line.chars().enumerate() {
-                if ch != '.' { 
+                if ch != '.' && !ch.is_whitespace() {
                     cells.push(Cell::new(row as u32, col as u32))
                 }
             }
@@ -60,11 +105,11 @@ impl PatternParser {
                         match ch {
                             'o' | 'x' | 'y' | 'z' => {
                                 if count == 0 {
-                                    cells.push(Cell::new(row as u32, col as u32));
+                                    cells.push(Cell::new(row, col));
                                     col += 1;
                                 } else {
                                     while count > 0 {
-                                        cells.push(Cell::new(row as u32, col as u32));
+                                        cells.push(Cell::new(row, col));
                                         col += 1;
                                         count -= 1;
                                     }
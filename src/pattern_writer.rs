@@ -0,0 +1,81 @@
+use crate::pattern::Pattern;
+
+use wasm_bindgen::prelude::*;
+
+// Golly and LifeViewer keep RLE lines no longer than this
+const RLE_LINE_LENGTH: usize = 70;
+
+#[wasm_bindgen]
+pub struct PatternWriter;
+
+#[wasm_bindgen]
+impl PatternWriter {
+    /// The pattern in the RLE format, with its `#` metadata lines, a header with its size and
+    /// the Life rule, and runs of cells wrapped to 70 characters.
+    pub fn to_rle_data(pattern: &Pattern) -> String {
+        let mut cells: Vec<(u32, u32)> = pattern
+            .cells()
+            .iter()
+            .map(|cell| (cell.row(), cell.col()))
+            .collect();
+        cells.sort_unstable();
+        cells.dedup();
+        let height = cells.iter().map(|(row, _)| row + 1).max().unwrap_or(0);
+        let width = cells.iter().map(|(_, col)| col + 1).max().unwrap_or(0);
+
+        // Runs of vacant cells ('b'), populated cells ('o') and ends of rows ('$')
+        let mut runs: Vec<(u32, char)> = vec![];
+        let mut push = |count: u32, tag: char| match runs.last_mut() {
+            Some((last_count, last_tag)) if *last_tag == tag => *last_count += count,
+            _ => runs.push((count, tag)),
+        };
+        let (mut row, mut col) = (0, 0);
+        cells.iter().for_each(|(cell_row, cell_col)| {
+            if *cell_row > row {
+                push(cell_row - row, '$');
+                row = *cell_row;
+                col = 0;
+            }
+            if *cell_col > col {
+                push(cell_col - col, 'b');
+            }
+            push(1, 'o');
+            col = cell_col + 1;
+        });
+
+        // Plaintext metadata becomes RLE name and comment lines
+        let mut data: String = pattern
+            .metadata()
+            .iter()
+            .filter_map(|line| {
+                if line.starts_with('#') {
+                    Some(format!("{}\n", line))
+                } else if let Some(name) = line.strip_prefix("!Name:") {
+                    Some(format!("#N {}\n", name.trim()))
+                } else {
+                    line.strip_prefix('!')
+                        .map(|comment| format!("#C {}\n", comment.trim()))
+                }
+            })
+            .collect();
+        data.push_str(&format!("x = {}, y = {}, rule = B3/S23\n", width, height));
+        let mut line = String::new();
+        runs.iter()
+            .map(|(count, tag)| match count {
+                1 => tag.to_string(),
+                _ => format!("{}{}", count, tag),
+            })
+            .chain(std::iter::once("!".to_string()))
+            .for_each(|run| {
+                if line.len() + run.len() > RLE_LINE_LENGTH {
+                    data.push_str(&line);
+                    data.push('\n');
+                    line.clear();
+                }
+                line.push_str(&run);
+            });
+        data.push_str(&line);
+        data.push('\n');
+        data
+    }
+}
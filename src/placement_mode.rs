@@ -0,0 +1,15 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// How `LifeGrid::place_pattern` combines a pattern's cells with the cells already there.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PlacementMode {
+    // Populates the pattern's cells
+    Or,
+    // Toggles the pattern's cells
+    Xor,
+    // Vacates the pattern's cells
+    AndNot,
+    // Vacates the pattern's bounding box, then populates the pattern's cells
+    Replace,
+}
@@ -0,0 +1,208 @@
+use crate::cell_state::CellState;
+use crate::engine::Engine;
+use std::collections::{HashMap, HashSet};
+
+const BRICK_SIZE: u32 = 8;
+
+// The row and column of a brick
+type BrickPosition = (u32, u32);
+
+/// An 8x8 block of cells, one bit per cell with row 0 in the low byte and column 0 in the low
+/// bit of each byte, along with the edge columns its neighbours need to compute their own next
+/// generation.
+struct Brick {
+    cells: u64,
+    west_column: u8, // Column 0, one bit per row
+    east_column: u8, // Column 7, one bit per row
+}
+
+impl Brick {
+    fn new(cells: u64) -> Self {
+        Self {
+            cells,
+            west_column: Self::column(cells, 0),
+            east_column: Self::column(cells, BRICK_SIZE - 1),
+        }
+    }
+    fn column(cells: u64, col: u32) -> u8 {
+        (0..BRICK_SIZE).fold(0, |column, row| {
+            column | ((((cells >> (row * BRICK_SIZE + col)) & 1) as u8) << row)
+        })
+    }
+    fn row(&self, row: u32) -> u16 {
+        ((self.cells >> (row * BRICK_SIZE)) & 0xFF) as u16
+    }
+}
+
+/// A QuickLife-style engine that splits the grid into bricks of 8x8 cells. Only non-empty
+/// bricks are stored, and only the bricks next to a change in the last generation are
+/// computed; the rest are asleep, since they can't change until a neighbour does.
+pub(crate) struct QuickLifeEngine {
+    cell_count: u32,
+    brick_count: u32, // Number of rows and columns of bricks
+    bricks: HashMap<BrickPosition, Brick>,
+    awake: HashSet<BrickPosition>, // Bricks that changed since the last step
+}
+
+impl QuickLifeEngine {
+    pub(crate) fn new(cells: &[CellState], cell_count: u32) -> Self {
+        let mut engine = Self {
+            cell_count,
+            brick_count: cell_count.div_ceil(BRICK_SIZE),
+            bricks: HashMap::new(),
+            awake: HashSet::new(),
+        };
+        cells
+            .iter()
+            .enumerate()
+            .filter(|(_, state)| **state == CellState::Populated)
+            .for_each(|(index, state)| engine.cell_edited(index, *state));
+        engine
+    }
+    fn cells_at(&self, position: BrickPosition) -> u64 {
+        self.bricks.get(&position).map_or(0, |brick| brick.cells)
+    }
+    fn set_cells_at(&mut self, position: BrickPosition, cells: u64) {
+        if cells == 0 {
+            self.bricks.remove(&position);
+        } else {
+            self.bricks.insert(position, Brick::new(cells));
+        }
+    }
+    /// The brick at an offset from `position`. Offsets off the top or left edge wrap around to
+    /// positions that are never used, so they are treated as empty.
+    fn neighbor(
+        &self,
+        (row, col): BrickPosition,
+        row_offset: i32,
+        col_offset: i32,
+    ) -> Option<&Brick> {
+        self.bricks.get(&(
+            row.wrapping_add(row_offset as u32),
+            col.wrapping_add(col_offset as u32),
+        ))
+    }
+    /// The bricks that could change in the next generation: the awake bricks and their
+    /// neighbours.
+    fn candidates(&self) -> HashSet<BrickPosition> {
+        let mut candidates = HashSet::new();
+        self.awake.iter().for_each(|(row, col)| {
+            (-1..=1).for_each(|row_offset: i32| {
+                (-1..=1).for_each(|col_offset: i32| {
+                    let row = row.wrapping_add(row_offset as u32);
+                    let col = col.wrapping_add(col_offset as u32);
+                    if row < self.brick_count && col < self.brick_count {
+                        candidates.insert((row, col));
+                    }
+                })
+            })
+        });
+        candidates
+    }
+    /// Computes the next generation of the brick at `position`.
+    fn next_cells(&self, position: BrickPosition) -> u64 {
+        let empty = Brick::new(0);
+        let brick = |row_offset, col_offset| {
+            self.neighbor(position, row_offset, col_offset)
+                .unwrap_or(&empty)
+        };
+
+        // The ten rows from the one above the brick to the one below, each with a cell from the
+        // west neighbour in bit 0 and a cell from the east neighbour in bit 9
+        let mut window = [0u16; BRICK_SIZE as usize + 2];
+        window[0] = (brick(-1, -1).row(BRICK_SIZE - 1) >> (BRICK_SIZE - 1))
+            | (brick(-1, 0).row(BRICK_SIZE - 1) << 1)
+            | ((brick(-1, 1).row(BRICK_SIZE - 1) & 1) << 9);
+        let (west, center, east) = (brick(0, -1), brick(0, 0), brick(0, 1));
+        (0..BRICK_SIZE).for_each(|row| {
+            window[row as usize + 1] = ((west.east_column >> row) & 1) as u16
+                | (center.row(row) << 1)
+                | ((((east.west_column >> row) & 1) as u16) << 9);
+        });
+        window[BRICK_SIZE as usize + 1] = (brick(1, -1).row(0) >> (BRICK_SIZE - 1))
+            | (brick(1, 0).row(0) << 1)
+            | ((brick(1, 1).row(0) & 1) << 9);
+
+        (0..BRICK_SIZE as usize).fold(0, |cells, row| {
+            let (above, current, below) = (window[row], window[row + 1], window[row + 2]);
+            let neighbors = [
+                above << 1,
+                above,
+                above >> 1,
+                current << 1,
+                current >> 1,
+                below << 1,
+                below,
+                below >> 1,
+            ];
+            // Add up the neighbours of all the cells in the row at once, in three bit planes.
+            // A count of eight wraps around to zero, which has the same outcome.
+            let (mut ones, mut twos, mut fours) = (0u16, 0u16, 0u16);
+            neighbors.iter().for_each(|neighbor| {
+                let ones_carry = ones & neighbor;
+                ones ^= neighbor;
+                let twos_carry = twos & ones_carry;
+                twos ^= ones_carry;
+                fours ^= twos_carry;
+            });
+            let next_row = twos & !fours & (ones | current);
+            cells | ((((next_row >> 1) & 0xFF) as u64) << (row * BRICK_SIZE as usize))
+        }) & self.mask(position)
+    }
+    /// The cells of the brick at `position` that are inside the grid.
+    fn mask(&self, (row, col): BrickPosition) -> u64 {
+        let rows = (self.cell_count - row * BRICK_SIZE).min(BRICK_SIZE);
+        let cols = (self.cell_count - col * BRICK_SIZE).min(BRICK_SIZE);
+        let row_mask = (1u64 << cols) - 1;
+        (0..rows).fold(0, |mask, row| mask | (row_mask << (row * BRICK_SIZE)))
+    }
+}
+
+impl Engine for QuickLifeEngine {
+    fn step(
+        &mut self,
+        _cells: &[CellState],
+        _cell_count: u32,
+        _thread_count: u32,
+    ) -> Vec<(usize, CellState)> {
+        let updates: Vec<(BrickPosition, u64)> = self
+            .candidates()
+            .into_iter()
+            .map(|position| (position, self.next_cells(position)))
+            .filter(|(position, cells)| *cells != self.cells_at(*position))
+            .collect();
+
+        let mut changes = vec![];
+        self.awake.clear();
+        updates.into_iter().for_each(|(position, cells)| {
+            let flipped = cells ^ self.cells_at(position);
+            (0..BRICK_SIZE * BRICK_SIZE)
+                .filter(|bit| (flipped >> bit) & 1 == 1)
+                .for_each(|bit| {
+                    let row = position.0 * BRICK_SIZE + bit / BRICK_SIZE;
+                    let col = position.1 * BRICK_SIZE + bit % BRICK_SIZE;
+                    let state = if (cells >> bit) & 1 == 1 {
+                        CellState::Populated
+                    } else {
+                        CellState::Vacant
+                    };
+                    changes.push(((row * self.cell_count + col) as usize, state));
+                });
+            self.set_cells_at(position, cells);
+            self.awake.insert(position);
+        });
+        changes
+    }
+    fn cell_edited(&mut self, index: usize, state: CellState) {
+        let row = index as u32 / self.cell_count;
+        let col = index as u32 % self.cell_count;
+        let position = (row / BRICK_SIZE, col / BRICK_SIZE);
+        let bit = 1u64 << ((row % BRICK_SIZE) * BRICK_SIZE + col % BRICK_SIZE);
+        let cells = match state {
+            CellState::Populated => self.cells_at(position) | bit,
+            _ => self.cells_at(position) & !bit,
+        };
+        self.set_cells_at(position, cells);
+        self.awake.insert(position);
+    }
+}
@@ -0,0 +1,55 @@
+use crate::color::Color;
+use crate::frame_buffer::FrameBuffer;
+
+/// A surface that a `LifeGrid` draws itself on. Coordinates are in grid pixels, with the
+/// outside corner of the grid's border at 0, 0.
+pub trait Renderer {
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color);
+    /// Fills a rectangle whose corners are rounded to `radius`, which is at most half its
+    /// width and height.
+    fn fill_rounded_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        radius: f64,
+        color: Color,
+    );
+    /// Draws the outline of a rectangle, with the line centred on its edges.
+    fn stroke_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        line_width: f64,
+        color: Color,
+    );
+    /// Fills the squares `size` pixels wide with top left corners at `corners`.
+    fn fill_cells(&mut self, corners: &[(f64, f64)], size: f64, color: Color);
+    /// Draws the outlines of the squares `size` pixels wide with top left corners at `corners`,
+    /// clipped to the squares, so that lines shared with squares that aren't drawn are left as
+    /// they are. Lines shared by two of the squares are drawn once.
+    fn stroke_cells(&mut self, corners: &[(f64, f64)], size: f64, line_width: f64, color: Color);
+    /// Restricts drawing to a rectangle until `unclip` is called.
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64);
+    fn unclip(&mut self);
+    /// Fits the surface to a grid `size` pixels square. Anything already drawn may be lost.
+    fn resize(&mut self, _size: f64) {}
+    /// The number of device pixels per grid pixel.
+    fn pixel_ratio(&self) -> f64 {
+        1.0
+    }
+    /// Whether `blit` can copy a frame buffer onto the surface.
+    fn can_blit(&self) -> bool {
+        false
+    }
+    /// Copies `frame_buffer`, which covers the whole surface at `pixel_ratio`, onto the
+    /// surface.
+    fn blit(&mut self, _frame_buffer: &FrameBuffer) {}
+    /// Converts a point in client coordinates, e.g. from a mouse event, to grid pixels.
+    fn point_from_client(&self, client_x: f64, client_y: f64) -> (f64, f64) {
+        (client_x, client_y)
+    }
+}
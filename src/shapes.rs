@@ -0,0 +1,98 @@
+/// The cells on a line from one cell to another that are in a grid `size` cells square. The
+/// line takes one cell at each step along its longer axis, with the other axis rounded to the
+/// nearest cell.
+pub(crate) fn line(from: (i32, i32), to: (i32, i32), size: i32) -> Vec<(i32, i32)> {
+    let deltas = (to.0 as i64 - from.0 as i64, to.1 as i64 - from.1 as i64);
+    let steps = deltas.0.abs().max(deltas.1.abs());
+    let along = |start: i32, delta: i64, step: i64| {
+        // Rounded half away from zero, in 128 bits as the product can exceed 64
+        let offset = if steps == 0 {
+            0
+        } else {
+            (2 * step as i128 * delta as i128 + steps as i128 * delta.signum() as i128)
+                / (2 * steps as i128)
+        };
+        (start as i64 + offset as i64) as i32
+    };
+
+    // Only the steps that are in the grid along the longer axis
+    let (start, delta) = if deltas.0.abs() >= deltas.1.abs() {
+        (from.0 as i64, deltas.0)
+    } else {
+        (from.1 as i64, deltas.1)
+    };
+    let (first, last) = if delta >= 0 {
+        (-start, size as i64 - 1 - start)
+    } else {
+        (start - size as i64 + 1, start)
+    };
+    (first.max(0)..=last.min(steps))
+        .map(|step| (along(from.0, deltas.0, step), along(from.1, deltas.1, step)))
+        .filter(|(row, col)| (0..size).contains(row) && (0..size).contains(col))
+        .collect()
+}
+
+/// The cells of a rectangle with corners at `from` and `to`, or just its edges if not
+/// `filled`, that are in a grid `size` cells square.
+pub(crate) fn rectangle(
+    from: (i32, i32),
+    to: (i32, i32),
+    filled: bool,
+    size: i32,
+) -> Vec<(i32, i32)> {
+    let (top, left, bottom, right) = bounds(from, to);
+    cells_in(from, to, size)
+        .filter(|(row, col)| {
+            filled || *row == top || *row == bottom || *col == left || *col == right
+        })
+        .collect()
+}
+
+/// The cells of the ellipse that fits in the rectangle with corners at `from` and `to`,
+/// or just its edge if not `filled`, that are in a grid `size` cells square. A cell is inside
+/// if its centre is.
+pub(crate) fn ellipse(
+    from: (i32, i32),
+    to: (i32, i32),
+    filled: bool,
+    size: i32,
+) -> Vec<(i32, i32)> {
+    let (top, left, bottom, right) = bounds(from, to);
+    let radius_y = ((bottom - top) as f64 + 1.0) / 2.0;
+    let radius_x = ((right - left) as f64 + 1.0) / 2.0;
+    let inside = |row: i32, col: i32| {
+        let y = (row as f64 + 0.5 - top as f64 - radius_y) / radius_y;
+        let x = (col as f64 + 0.5 - left as f64 - radius_x) / radius_x;
+        x * x + y * y <= 1.0
+    };
+    cells_in(from, to, size)
+        .filter(|(row, col)| inside(*row, *col))
+        // The edge is the cells next to one outside
+        .filter(|(row, col)| {
+            filled
+                || !inside(row - 1, *col)
+                || !inside(row + 1, *col)
+                || !inside(*row, col - 1)
+                || !inside(*row, col + 1)
+        })
+        .collect()
+}
+
+/// The top, left, bottom and right of the rectangle with corners at `from` and `to`.
+fn bounds(from: (i32, i32), to: (i32, i32)) -> (i32, i32, i32, i32) {
+    (
+        from.0.min(to.0),
+        from.1.min(to.1),
+        from.0.max(to.0),
+        from.1.max(to.1),
+    )
+}
+
+/// The cells of the rectangle with corners at `from` and `to` that are in a grid `size` cells
+/// square.
+fn cells_in(from: (i32, i32), to: (i32, i32), size: i32) -> impl Iterator<Item = (i32, i32)> {
+    let (top, left, bottom, right) = bounds(from, to);
+    let (top, left) = (top.max(0), left.max(0));
+    let (bottom, right) = (bottom.min(size - 1), right.min(size - 1));
+    (top..=bottom).flat_map(move |row| (left..=right).map(move |col| (row, col)))
+}
@@ -0,0 +1,156 @@
+use crate::color::Color;
+use crate::renderer::Renderer;
+use std::fmt::Write;
+
+/// Builds an SVG document, in pixels from the top left.
+pub(crate) struct SvgDocument {
+    width: f64,
+    height: f64,
+    body: String,
+    clip_count: u32, // Number of clip paths defined so far, to give each a unique ID
+    clipped: bool,   // Whether the body is inside a clipped group
+}
+
+impl SvgDocument {
+    pub(crate) fn new(width: f64, height: f64) -> Self {
+        Self {
+            width,
+            height,
+            body: String::new(),
+            clip_count: 0,
+            clipped: false,
+        }
+    }
+    /// Strokes a path made of `M`, `H` and `V` commands.
+    pub(crate) fn path(&mut self, commands: &str, line_width: f64, color: Color) {
+        writeln!(
+            self.body,
+            r#"<path d="{}" stroke="{}" stroke-width="{}" fill="none"/>"#,
+            commands,
+            color.to_css(),
+            line_width
+        )
+        .unwrap();
+    }
+    /// Path commands for the squares `size` pixels wide with top left corners at `corners`.
+    fn squares(corners: &[(f64, f64)], size: f64) -> String {
+        let mut commands = String::new();
+        corners.iter().for_each(|(x, y)| {
+            write!(commands, "M{} {}h{}v{}h{}z", x, y, size, size, -size).unwrap();
+        });
+        commands
+    }
+    pub(crate) fn finish(mut self) -> String {
+        self.unclip();
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" "#,
+                r#"viewBox="0 0 {0} {1}">"#,
+                "\n{2}</svg>\n"
+            ),
+            self.width, self.height, self.body
+        )
+    }
+}
+
+impl Renderer for SvgDocument {
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color) {
+        writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            color.to_css()
+        )
+        .unwrap();
+    }
+    fn fill_rounded_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        radius: f64,
+        color: Color,
+    ) {
+        writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}" fill="{}"/>"#,
+            x,
+            y,
+            width,
+            height,
+            radius,
+            color.to_css()
+        )
+        .unwrap();
+    }
+    fn stroke_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        line_width: f64,
+        color: Color,
+    ) {
+        writeln!(
+            self.body,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" stroke="{}" stroke-width="{}" fill="none"/>"#,
+            x,
+            y,
+            width,
+            height,
+            color.to_css(),
+            line_width
+        )
+        .unwrap();
+    }
+    fn fill_cells(&mut self, corners: &[(f64, f64)], size: f64, color: Color) {
+        if corners.is_empty() {
+            return;
+        }
+        writeln!(
+            self.body,
+            r#"<path d="{}" fill="{}"/>"#,
+            Self::squares(corners, size),
+            color.to_css()
+        )
+        .unwrap();
+    }
+    fn stroke_cells(&mut self, corners: &[(f64, f64)], size: f64, line_width: f64, color: Color) {
+        if corners.is_empty() {
+            return;
+        }
+        let squares = Self::squares(corners, size);
+        self.clip_count += 1;
+        writeln!(
+            self.body,
+            r#"<clipPath id="clip{0}"><path d="{1}"/></clipPath><path d="{1}" stroke="{2}" stroke-width="{3}" fill="none" clip-path="url(#clip{0})"/>"#,
+            self.clip_count,
+            squares,
+            color.to_css(),
+            line_width
+        )
+        .unwrap();
+    }
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.unclip();
+        self.clip_count += 1;
+        writeln!(
+            self.body,
+            r#"<clipPath id="clip{0}"><rect x="{1}" y="{2}" width="{3}" height="{4}"/></clipPath><g clip-path="url(#clip{0})">"#,
+            self.clip_count, x, y, width, height
+        )
+        .unwrap();
+        self.clipped = true;
+    }
+    fn unclip(&mut self) {
+        if self.clipped {
+            self.body.push_str("</g>\n");
+            self.clipped = false;
+        }
+    }
+}
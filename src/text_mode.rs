@@ -0,0 +1,12 @@
+use wasm_bindgen::prelude::wasm_bindgen;
+
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextMode {
+    // One `O` or `.` per cell, as in the plaintext pattern format
+    Plain,
+    // One half-block character per cell and the cell below it
+    HalfBlock,
+    // One braille character per block of 2 columns by 4 rows of cells
+    Braille,
+}
@@ -0,0 +1,77 @@
+use crate::text_mode::TextMode;
+use std::ops::Range;
+
+/// Writes a rectangle of cells as lines of text, one character per cell or block of cells.
+pub(crate) struct TextRenderer {
+    mode: TextMode,
+}
+
+impl TextRenderer {
+    pub(crate) fn new(mode: TextMode) -> Self {
+        Self { mode }
+    }
+    /// The cells in `rows` and `cols`, where `populated` tells whether the cell at a row and
+    /// column is populated. Blocks that run past the rectangle are padded with vacant cells.
+    pub(crate) fn render(
+        &self,
+        rows: Range<u32>,
+        cols: Range<u32>,
+        populated: impl Fn(u32, u32) -> bool,
+    ) -> String {
+        let (block_height, block_width) = match self.mode {
+            TextMode::Plain => (1, 1),
+            TextMode::HalfBlock => (2, 1),
+            TextMode::Braille => (4, 2),
+        };
+        let is_populated =
+            |row: u32, col: u32| rows.contains(&row) && cols.contains(&col) && populated(row, col);
+        let mut text = String::new();
+        rows.clone().step_by(block_height).for_each(|row| {
+            cols.clone().step_by(block_width).for_each(|col| {
+                text.push(self.block(|row_offset, col_offset| {
+                    is_populated(row + row_offset, col + col_offset)
+                }));
+            });
+            text.push('\n');
+        });
+        text
+    }
+    /// The character for the block of cells whose top left cell is at offset 0, 0.
+    fn block(&self, populated: impl Fn(u32, u32) -> bool) -> char {
+        match self.mode {
+            TextMode::Plain => {
+                if populated(0, 0) {
+                    'O'
+                } else {
+                    '.'
+                }
+            }
+            TextMode::HalfBlock => match (populated(0, 0), populated(1, 0)) {
+                (true, true) => '\u{2588}',
+                (true, false) => '\u{2580}',
+                (false, true) => '\u{2584}',
+                (false, false) => ' ',
+            },
+            TextMode::Braille => {
+                // The dots are numbered down the left column and then the right, with the
+                // bottom row of each added afterwards
+                const DOTS: [(u32, u32); 8] = [
+                    (0, 0),
+                    (1, 0),
+                    (2, 0),
+                    (0, 1),
+                    (1, 1),
+                    (2, 1),
+                    (3, 0),
+                    (3, 1),
+                ];
+                let bits = DOTS
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, (row, col))| populated(*row, *col))
+                    .fold(0, |bits, (dot, _)| bits | (1 << dot));
+                std::char::from_u32(0x2800 + bits).expect("braille patterns should be characters")
+            }
+        }
+    }
+}
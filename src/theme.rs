@@ -0,0 +1,175 @@
+use crate::cell_state::CellState;
+use crate::color::Color;
+use wasm_bindgen::prelude::*;
+
+/// The colours used to draw a `LifeGrid`.
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    border: Color,
+    border_highlight: Color,
+    grid_line: Color,
+    vacant: Color,
+    populated: Color,
+    invalid: Color,
+    // Colours of populated cells by age, when colouring by age
+    newborn: Color,
+    young: Color,
+    old: Color,
+    ancient: Color,
+    // Colours that vacant cells tend towards in the trail and heatmap overlays
+    trail: Color,
+    heat: Color,
+    // Colour of outlines drawn over the cells, such as the minimap's view of the grid
+    highlight: Color,
+}
+
+#[wasm_bindgen]
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            border: Color::new(170, 170, 0),
+            border_highlight: Color::new(255, 255, 170),
+            grid_line: Color::new(0, 0, 0),
+            vacant: Color::new(170, 170, 170),
+            populated: Color::new(10, 10, 0),
+            invalid: Color::new(200, 0, 0),
+            newborn: Color::new(0, 120, 0),
+            young: Color::new(0, 60, 120),
+            old: Color::new(60, 0, 120),
+            ancient: Color::new(10, 10, 0),
+            trail: Color::new(120, 150, 120),
+            heat: Color::new(220, 60, 0),
+            highlight: Color::new(220, 0, 0),
+        }
+    }
+    pub fn dark() -> Self {
+        Self {
+            border: Color::new(60, 60, 70),
+            border_highlight: Color::new(100, 100, 120),
+            grid_line: Color::new(45, 45, 50),
+            vacant: Color::new(25, 25, 30),
+            populated: Color::new(220, 220, 200),
+            invalid: Color::new(200, 60, 60),
+            newborn: Color::new(120, 255, 120),
+            young: Color::new(120, 200, 255),
+            old: Color::new(200, 140, 255),
+            ancient: Color::new(220, 220, 200),
+            trail: Color::new(40, 90, 60),
+            heat: Color::new(255, 120, 0),
+            highlight: Color::new(255, 200, 0),
+        }
+    }
+    pub fn high_contrast() -> Self {
+        Self {
+            border: Color::new(255, 255, 255),
+            border_highlight: Color::new(0, 0, 0),
+            grid_line: Color::new(128, 128, 128),
+            vacant: Color::new(0, 0, 0),
+            populated: Color::new(255, 255, 0),
+            invalid: Color::new(255, 0, 255),
+            newborn: Color::new(0, 255, 0),
+            young: Color::new(0, 255, 255),
+            old: Color::new(255, 128, 0),
+            ancient: Color::new(255, 255, 0),
+            trail: Color::new(0, 128, 255),
+            heat: Color::new(255, 0, 0),
+            highlight: Color::new(0, 160, 255),
+        }
+    }
+    pub fn golly_classic() -> Self {
+        Self {
+            border: Color::new(128, 128, 128),
+            border_highlight: Color::new(192, 192, 192),
+            grid_line: Color::new(208, 208, 208),
+            vacant: Color::new(255, 255, 255),
+            populated: Color::new(0, 0, 0),
+            invalid: Color::new(255, 0, 0),
+            newborn: Color::new(0, 160, 0),
+            young: Color::new(0, 0, 200),
+            old: Color::new(128, 0, 128),
+            ancient: Color::new(0, 0, 0),
+            trail: Color::new(180, 210, 255),
+            heat: Color::new(255, 100, 100),
+            highlight: Color::new(0, 128, 255),
+        }
+    }
+    /// The preset called `name`: "light", "dark", "high-contrast" or "golly-classic".
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "light" => Some(Self::light()),
+            "dark" => Some(Self::dark()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "golly-classic" => Some(Self::golly_classic()),
+            _ => None,
+        }
+    }
+    /// The CSS colour of cells in `state`.
+    pub fn state_color(&self, state: CellState) -> String {
+        self.cell_color(state).to_css()
+    }
+    pub fn set_state_color(&mut self, state: CellState, red: u8, green: u8, blue: u8) {
+        let color = Color::new(red, green, blue);
+        match state {
+            CellState::Vacant => self.vacant = color,
+            CellState::Populated => self.populated = color,
+            CellState::Invalid => self.invalid = color,
+        }
+    }
+}
+impl Theme {
+    // Ages at which populated cells are exactly the newborn, young, old and ancient colours
+    pub(crate) const NEWBORN_AGE: u32 = 0;
+    pub(crate) const YOUNG_AGE: u32 = 4;
+    pub(crate) const OLD_AGE: u32 = 32;
+    pub(crate) const ANCIENT_AGE: u32 = 256;
+
+    pub(crate) fn cell_color(&self, state: CellState) -> Color {
+        match state {
+            CellState::Vacant => self.vacant,
+            CellState::Populated => self.populated,
+            CellState::Invalid => self.invalid,
+        }
+    }
+    /// The colour of a populated cell that has been alive for `age` generations, on a
+    /// gradient from newborn through young and old to ancient.
+    pub(crate) fn age_color(&self, age: u32) -> Color {
+        let stops = [
+            (Self::NEWBORN_AGE, self.newborn),
+            (Self::YOUNG_AGE, self.young),
+            (Self::OLD_AGE, self.old),
+            (Self::ANCIENT_AGE, self.ancient),
+        ];
+        stops
+            .windows(2)
+            .find(|stop| age < stop[1].0)
+            .map_or(self.ancient, |stop| {
+                let ((from_age, from), (to_age, to)) = (stop[0], stop[1]);
+                from.interpolate(to, (age - from_age) as f64 / (to_age - from_age) as f64)
+            })
+    }
+    pub(crate) fn trail(&self) -> Color {
+        self.trail
+    }
+    pub(crate) fn heat(&self) -> Color {
+        self.heat
+    }
+    pub(crate) fn border(&self) -> Color {
+        self.border
+    }
+    pub(crate) fn border_highlight(&self) -> Color {
+        self.border_highlight
+    }
+    pub(crate) fn grid_line(&self) -> Color {
+        self.grid_line
+    }
+    pub(crate) fn highlight(&self) -> Color {
+        self.highlight
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::light()
+    }
+}
@@ -0,0 +1,65 @@
+use crate::consts::Constants;
+
+/// The part of the grid shown on the canvas. Positions are in pixels from the top left of the
+/// area inside the border, and `size` is the width and height of that area.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Viewport {
+    zoom: f64,     // Magnification of the grid, 1.0 shows all of it
+    offset_x: f64, // Distance of the left edge of the view from the left of the zoomed grid
+    offset_y: f64, // Distance of the top edge of the view from the top of the zoomed grid
+}
+
+impl Viewport {
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+    pub fn offset_x(&self) -> f64 {
+        self.offset_x
+    }
+    pub fn offset_y(&self) -> f64 {
+        self.offset_y
+    }
+    /// Multiplies the zoom by `factor`, keeping the point at `x`, `y` in the same place.
+    pub fn zoom_at(&mut self, x: f64, y: f64, factor: f64, size: f64) {
+        let (grid_x, grid_y) = self.to_grid(x, y);
+        self.zoom = (self.zoom * factor).clamp(1.0, Constants::MAX_ZOOM);
+        self.offset_x = grid_x * self.zoom - x;
+        self.offset_y = grid_y * self.zoom - y;
+        self.clamp_offsets(size);
+    }
+    /// Moves the grid by `dx`, `dy` pixels, as far as its edges.
+    pub fn pan_by(&mut self, dx: f64, dy: f64, size: f64) {
+        self.offset_x -= dx;
+        self.offset_y -= dy;
+        self.clamp_offsets(size);
+    }
+    /// Moves the view so that the point at `x`, `y` on the unzoomed grid is in its centre, or
+    /// as near as the grid's edges allow.
+    pub fn center_on(&mut self, x: f64, y: f64, size: f64) {
+        self.offset_x = x * self.zoom - size / 2.0;
+        self.offset_y = y * self.zoom - size / 2.0;
+        self.clamp_offsets(size);
+    }
+    /// Converts a point in the view to a point on the unzoomed grid.
+    pub fn to_grid(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            (x + self.offset_x) / self.zoom,
+            (y + self.offset_y) / self.zoom,
+        )
+    }
+    fn clamp_offsets(&mut self, size: f64) {
+        let max_offset = size * (self.zoom - 1.0);
+        self.offset_x = self.offset_x.clamp(0.0, max_offset);
+        self.offset_y = self.offset_y.clamp(0.0, max_offset);
+    }
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            offset_x: 0.0,
+            offset_y: 0.0,
+        }
+    }
+}
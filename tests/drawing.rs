@@ -0,0 +1,66 @@
+//! Tests of drawing lines, rectangles and ellipses and flood filling.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+fn text(life_grid: &LifeGrid) -> String {
+    life_grid.to_text(TextMode::Plain, false)
+}
+
+#[test]
+fn shapes() {
+    let mut life_grid = LifeGrid::new_headless(80, 8);
+    let (from, to) = (Cell::new(1, 1), Cell::new(5, 6));
+    life_grid.draw_rectangle(&from, &to, false, CellState::Populated);
+    assert_eq!(
+        text(&life_grid),
+        "........\n.OOOOOO.\n.O....O.\n.O....O.\n.O....O.\n.OOOOOO.\n........\n........\n"
+    );
+    life_grid.vacate_all_cells();
+    life_grid.draw_ellipse(&from, &to, true, CellState::Populated);
+    assert_eq!(
+        text(&life_grid),
+        "........\n..OOOO..\n.OOOOOO.\n.OOOOOO.\n.OOOOOO.\n..OOOO..\n........\n........\n"
+    );
+    life_grid.vacate_all_cells();
+    life_grid.draw_line(&Cell::new(0, 0), &Cell::new(3, 7), CellState::Populated);
+    assert_eq!(
+        text(&life_grid),
+        "OO......\n..OO....\n....OO..\n......OO\n........\n........\n........\n........\n"
+    );
+}
+
+#[test]
+fn shapes_far_past_the_grid_are_clipped() {
+    let mut life_grid = LifeGrid::new_headless(100, 10);
+    let (from, to) = (Cell::new(0, 0), Cell::new(100_000, 100_000));
+    life_grid.draw_rectangle(&from, &to, true, CellState::Populated);
+    assert_eq!(life_grid.population(), 100);
+    life_grid.draw_ellipse(&from, &to, false, CellState::Vacant);
+    assert_eq!(life_grid.population(), 100);
+    life_grid.draw_rectangle(&from, &to, false, CellState::Vacant);
+    assert_eq!(life_grid.population(), 81);
+    life_grid.draw_line(&Cell::new(u32::MAX, 5), &Cell::new(0, 5), CellState::Vacant);
+    assert_eq!(life_grid.population(), 72);
+}
+
+#[test]
+fn flood_fill_stops_at_edges() {
+    let mut life_grid = LifeGrid::new_headless(80, 8);
+    life_grid.draw_rectangle(
+        &Cell::new(1, 1),
+        &Cell::new(5, 6),
+        false,
+        CellState::Populated,
+    );
+    life_grid.flood_fill(3, 3, CellState::Populated);
+    assert_eq!(life_grid.population(), 30);
+    life_grid.flood_fill(0, 0, CellState::Populated);
+    assert_eq!(life_grid.population(), 64);
+    // Filling with the invalid state fills with vacant cells, which are stored instead
+    life_grid.flood_fill(0, 0, CellState::Invalid);
+    assert_eq!(life_grid.population(), 0);
+    life_grid.flood_fill(0, 0, CellState::Invalid);
+    assert_eq!(life_grid.population(), 0);
+}
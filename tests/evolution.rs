@@ -0,0 +1,157 @@
+//! Tests that every engine and thread count evolves the grid like a plain step of the rules,
+//! and that the statistics of each cell's activity follow.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+/// A scattering of about a third of the cells, the same on every run.
+fn soup(cell_count: u32) -> Vec<bool> {
+    let mut seed: u32 = 12345;
+    (0..cell_count * cell_count)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16).is_multiple_of(3)
+        })
+        .collect()
+}
+
+/// The generation that follows `cells`, with the cells outside the grid vacant.
+fn reference_step(cells: &[bool], cell_count: u32) -> Vec<bool> {
+    let count = cell_count as i32;
+    let populated = |row: i32, col: i32| {
+        (0..count).contains(&row)
+            && (0..count).contains(&col)
+            && cells[(row * count + col) as usize]
+    };
+    (0..count * count)
+        .map(|index| {
+            let (row, col) = (index / count, index % count);
+            let neighbors = (-1..=1)
+                .flat_map(|row_offset| (-1..=1).map(move |col_offset| (row_offset, col_offset)))
+                .filter(|offsets| *offsets != (0, 0))
+                .filter(|(row_offset, col_offset)| populated(row + row_offset, col + col_offset))
+                .count();
+            neighbors == 3 || (neighbors == 2 && populated(row, col))
+        })
+        .collect()
+}
+
+/// Evolves a soup for 20 generations with `engine_kind`, checking every cell against
+/// `reference_step`.
+fn assert_evolves_like_reference(cell_count: u32, engine_kind: EngineKind, thread_count: u32) {
+    let mut life_grid = LifeGrid::new_headless(400, cell_count);
+    life_grid.set_engine(engine_kind);
+    life_grid.set_thread_count(thread_count);
+    let mut cells = soup(cell_count);
+    (0..cell_count * cell_count)
+        .filter(|index| cells[*index as usize])
+        .for_each(|index| {
+            life_grid.set_cell_state(index / cell_count, index % cell_count, CellState::Populated)
+        });
+    (0..20).for_each(|_| {
+        life_grid.evolve();
+        cells = reference_step(&cells, cell_count);
+        (0..cell_count * cell_count).for_each(|index| {
+            let expected = if cells[index as usize] {
+                CellState::Populated
+            } else {
+                CellState::Vacant
+            };
+            assert_eq!(
+                life_grid.cell_state(index / cell_count, index % cell_count),
+                expected
+            );
+        });
+    });
+}
+
+#[test]
+fn bands_match_reference_step() {
+    [1, 2, 3, 7].iter().for_each(|thread_count| {
+        assert_evolves_like_reference(37, EngineKind::Naive, *thread_count)
+    });
+}
+
+#[test]
+fn quick_life_matches_reference_step() {
+    [37, 64].iter().for_each(|cell_count| {
+        assert_evolves_like_reference(*cell_count, EngineKind::QuickLife, 1)
+    });
+}
+
+#[test]
+fn births_and_deaths_are_counted() {
+    [EngineKind::Naive, EngineKind::QuickLife]
+        .iter()
+        .for_each(|engine_kind| {
+            let mut life_grid = LifeGrid::new_headless(400, 12);
+            life_grid.set_engine(*engine_kind);
+            // A glider, which loses two cells and gains two each generation
+            [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]
+                .iter()
+                .for_each(|(row, col)| life_grid.set_cell_state(*row, *col, CellState::Populated));
+            life_grid.evolve();
+            assert_eq!(life_grid.births(), 2);
+            assert_eq!(life_grid.deaths(), 2);
+            assert_eq!(life_grid.population(), 5);
+        });
+}
+
+#[test]
+fn invalid_cells_are_stored_as_vacant() {
+    let mut life_grid = LifeGrid::new_headless(400, 3);
+    life_grid.set_cell_state(1, 1, CellState::Invalid);
+    assert_eq!(life_grid.cell_state(1, 1), CellState::Vacant);
+}
+
+#[test]
+fn thread_count_is_at_most_one_per_row() {
+    let mut life_grid = LifeGrid::new_headless(400, 10);
+    life_grid.set_thread_count(1000);
+    assert_eq!(life_grid.thread_count(), 10);
+    life_grid.set_thread_count(0);
+    assert_eq!(life_grid.thread_count(), 1);
+}
+
+#[test]
+fn ages_count_generations_alive() {
+    let mut life_grid = LifeGrid::new_headless(400, 10);
+    life_grid.set_age_coloring(true);
+    // A horizontal blinker, whose centre never changes
+    (3..6).for_each(|col| life_grid.set_cell_state(4, col, CellState::Populated));
+    (0..3).for_each(|_| {
+        life_grid.evolve();
+        life_grid.draw();
+    });
+    // Vertical again, with the ends born in the last generation
+    assert_eq!(life_grid.cell_age(4, 4), 3);
+    assert_eq!(life_grid.cell_age(3, 4), 0);
+    assert_eq!(life_grid.cell_age(4, 3), 0);
+    life_grid.evolve();
+    assert_eq!(life_grid.cell_age(4, 4), 4);
+    assert_eq!(life_grid.cell_age(4, 3), 0);
+    assert_eq!(life_grid.cell_age(3, 4), 0);
+}
+
+#[test]
+fn heat_counts_generations_alive_or_flips() {
+    let mut life_grid = LifeGrid::new_headless(400, 10);
+    [HeatMetric::Alive, HeatMetric::Flips]
+        .iter()
+        .for_each(|heat_metric| {
+            life_grid.vacate_all_cells();
+            life_grid.set_heat_metric(*heat_metric);
+            // A horizontal blinker
+            (3..6).for_each(|col| life_grid.set_cell_state(4, col, CellState::Populated));
+            (0..3).for_each(|_| life_grid.evolve());
+            let heat = life_grid.heat_counts();
+            let expected = match heat_metric {
+                // The centre is always alive, the ends alive for two of the generations
+                HeatMetric::Alive => [3, 2, 1],
+                // The ends flip every generation
+                HeatMetric::Flips => [0, 3, 3],
+            };
+            assert_eq!([heat[44], heat[43], heat[34]], expected);
+        });
+}
@@ -0,0 +1,83 @@
+//! Tests of exporting the grid as images and text.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+fn glider(life_grid: &mut LifeGrid) {
+    [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]
+        .iter()
+        .for_each(|(row, col)| life_grid.set_cell_state(*row, *col, CellState::Populated));
+}
+
+/// The width and height of a PNG image.
+fn png_size(png: &[u8]) -> (u32, u32) {
+    let reader = png::Decoder::new(png)
+        .read_info()
+        .expect("export should be a PNG image");
+    let info = reader.info();
+    (info.width, info.height)
+}
+
+#[test]
+fn png_is_scaled_and_cropped() {
+    let mut life_grid = LifeGrid::new_headless(80, 8);
+    glider(&mut life_grid);
+    assert_eq!(png_size(&life_grid.to_png(4, false)), (32, 32));
+    assert_eq!(png_size(&life_grid.to_png(4, true)), (12, 12));
+    assert_eq!(
+        png_size(&life_grid.to_png_region(&Cell::new(3, 3), &Cell::new(1, 0), 2)),
+        (8, 6)
+    );
+}
+
+#[test]
+fn large_png_scales_are_reduced() {
+    let life_grid = LifeGrid::new_headless(400, 100);
+    assert_eq!(png_size(&life_grid.to_png(1000, false)), (4000, 4000));
+    let cell = Cell::new(5, 5);
+    assert_eq!(
+        png_size(&life_grid.to_png_region(&cell, &cell, u32::MAX)),
+        (4096, 4096)
+    );
+}
+
+#[test]
+fn gif_runs_ahead_on_a_copy() {
+    let mut life_grid = LifeGrid::new_headless(80, 8);
+    glider(&mut life_grid);
+    let gif = life_grid.to_gif(4, 100, 2, true);
+    let mut decoder = gif::DecodeOptions::new()
+        .read_info(&gif[..])
+        .expect("export should be a GIF image");
+    // The glider moves one cell down and right over the four generations
+    assert_eq!((decoder.width(), decoder.height()), (8, 8));
+    let mut frames = 0;
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        assert_eq!(frame.delay, 10);
+        frames += 1;
+    }
+    assert_eq!(frames, 4);
+    let min = life_grid.bounding_box_min().unwrap();
+    assert_eq!((min.row(), min.col()), (1, 1));
+}
+
+#[test]
+fn text_modes() {
+    let mut life_grid = LifeGrid::new_headless(80, 8);
+    glider(&mut life_grid);
+    assert_eq!(life_grid.to_text(TextMode::Plain, true), ".O.\n..O\nOOO\n");
+    assert_eq!(
+        life_grid.to_text(TextMode::HalfBlock, true),
+        " \u{2580}\u{2584}\n\u{2580}\u{2580}\u{2580}\n"
+    );
+    // Dots 3, 4 and 6 in the first block, and 2 and 3 in the second
+    assert_eq!(
+        life_grid.to_text(TextMode::Braille, true),
+        "\u{282c}\u{2806}\n"
+    );
+    assert_eq!(
+        life_grid.to_text_region(&Cell::new(0, 0), &Cell::new(1, 3), TextMode::Plain),
+        "....\n..O.\n"
+    );
+}
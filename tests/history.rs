@@ -0,0 +1,100 @@
+//! Tests of undoing and redoing edits, transforms and runs of generations.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+fn text(life_grid: &LifeGrid) -> String {
+    life_grid.to_text(TextMode::Plain, false)
+}
+
+fn glider(engine_kind: EngineKind) -> LifeGrid {
+    let mut life_grid = LifeGrid::new_headless(120, 12);
+    life_grid.set_engine(engine_kind);
+    [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]
+        .iter()
+        .for_each(|(row, col)| life_grid.set_cell_state(*row, *col, CellState::Populated));
+    life_grid
+}
+
+#[test]
+fn undo_and_redo_restore_each_step() {
+    [EngineKind::Naive, EngineKind::QuickLife]
+        .iter()
+        .for_each(|engine_kind| {
+            let mut life_grid = glider(*engine_kind);
+            let start = text(&life_grid);
+            life_grid.rotate_clockwise();
+            let rotated = text(&life_grid);
+            // A run of generations is undone in one step
+            (0..3).for_each(|_| life_grid.evolve());
+            let evolved = text(&life_grid);
+            life_grid.vacate_all_cells();
+
+            assert!(life_grid.undo());
+            assert_eq!(text(&life_grid), evolved);
+            assert!(life_grid.undo());
+            assert_eq!(text(&life_grid), rotated);
+            assert!(life_grid.undo());
+            assert_eq!(text(&life_grid), start);
+            assert!(life_grid.redo());
+            assert_eq!(text(&life_grid), rotated);
+            assert!(life_grid.redo());
+            assert_eq!(text(&life_grid), evolved);
+
+            // The engine carries on from the restored cells
+            life_grid.undo();
+            life_grid.evolve();
+            let mut fresh = glider(EngineKind::Naive);
+            fresh.rotate_clockwise();
+            fresh.evolve();
+            assert_eq!(text(&life_grid), text(&fresh));
+        });
+}
+
+#[test]
+fn editing_clears_redo() {
+    let mut life_grid = glider(EngineKind::Naive);
+    life_grid.flip_vertical();
+    assert!(life_grid.undo());
+    assert!(life_grid.can_redo());
+    life_grid.set_cell_state(0, 0, CellState::Populated);
+    assert!(!life_grid.can_redo());
+    assert!(!life_grid.redo());
+}
+
+#[test]
+fn undoing_everything_empties_the_grid() {
+    let mut life_grid = glider(EngineKind::Naive);
+    life_grid.evolve();
+    while life_grid.undo() {}
+    assert!(!life_grid.can_undo());
+    assert_eq!(text(&life_grid), "............\n".repeat(12));
+}
+
+#[test]
+fn budget_limits_history() {
+    let mut life_grid = glider(EngineKind::Naive);
+    assert!(life_grid.can_undo());
+    life_grid.set_history_budget(0);
+    assert!(!life_grid.can_undo());
+    life_grid.evolve();
+    assert!(!life_grid.can_undo());
+    // A run whose starting cells don't fit forgets the edits before it
+    life_grid.set_history_budget(100);
+    life_grid.set_cell_state(0, 0, CellState::Populated);
+    assert!(life_grid.can_undo());
+    life_grid.evolve();
+    assert!(!life_grid.can_undo());
+}
+
+#[test]
+fn replacing_every_cell_is_one_step() {
+    let mut life_grid = glider(EngineKind::Naive);
+    let start = text(&life_grid);
+    let mut bytes = vec![0; 144];
+    bytes[0] = 1;
+    life_grid.set_cells_from_bytes(&bytes);
+    assert!(life_grid.undo());
+    assert_eq!(text(&life_grid), start);
+}
@@ -0,0 +1,74 @@
+//! Tests of reading and writing patterns in the RLE and plaintext formats.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+fn cells(pattern: &Pattern) -> Vec<(u32, u32)> {
+    let mut cells: Vec<(u32, u32)> = pattern
+        .cells()
+        .iter()
+        .map(|cell| (cell.row(), cell.col()))
+        .collect();
+    cells.sort_unstable();
+    cells
+}
+
+fn pattern_of(cells: &[(u32, u32)]) -> Pattern {
+    Pattern::new(
+        vec!["!Name: Test".to_string(), "!A comment".to_string()],
+        cells
+            .iter()
+            .map(|(row, col)| Cell::new(*row, *col))
+            .collect(),
+    )
+}
+
+#[test]
+fn rle_round_trip_keeps_cells() {
+    // A glider, a gap, and a long row that has to be wrapped
+    let mut original = vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2), (6, 40)];
+    original.extend((0..150).filter(|col| col % 3 != 0).map(|col| (9, col)));
+    original.sort_unstable();
+    let rle = PatternWriter::to_rle_data(&pattern_of(&original));
+
+    assert!(rle.lines().all(|line| line.len() <= 70));
+    assert!(rle.starts_with("#N Test\n#C A comment\nx = 150, y = 10, rule = B3/S23\n"));
+    assert!(rle.trim_end().ends_with('!'));
+    let parsed = PatternParser::parse_rle_data(&rle);
+    assert_eq!(cells(&parsed), original);
+}
+
+#[test]
+fn grid_clipboard_round_trips_through_rle() {
+    let mut life_grid = LifeGrid::new_headless(200, 20);
+    [(3, 4), (4, 5), (5, 3), (5, 4), (5, 5)]
+        .iter()
+        .for_each(|(row, col)| life_grid.set_cell_state(*row, *col, CellState::Populated));
+    life_grid.select(&Cell::new(2, 2), &Cell::new(6, 6));
+    life_grid.copy_selection();
+    let copied = life_grid.clipboard().expect("selection should be copied");
+    let parsed = PatternParser::parse_data(&PatternWriter::to_rle_data(&copied));
+    assert_eq!(cells(&parsed), cells(&copied));
+}
+
+#[test]
+fn parse_data_recognises_both_formats() {
+    let rle = PatternParser::parse_data("#C Glider\nx = 3, y = 3\nbo$2bo$3o!\n");
+    assert_eq!(cells(&rle), vec![(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]);
+    let plaintext = PatternParser::parse_data("!Name: Glider\n.O.\n..O\nOOO\n");
+    assert_eq!(cells(&plaintext), cells(&rle));
+}
+
+#[test]
+fn only_patterns_are_recognised() {
+    assert!(PatternParser::is_pattern_data("#N Glider\nx = 3, y = 3\nbo$2bo$\n3o!\n"));
+    assert!(PatternParser::is_pattern_data("!Name: Glider\n.O.\n..O\nOOO\n"));
+    assert!(PatternParser::is_pattern_data("*.*\n.*.\n"));
+    assert!(!PatternParser::is_pattern_data("https://conwaylife.com/wiki/Glider"));
+    assert!(PatternParser::is_pattern_data("x = 0, y = 0\n!\n"));
+    assert!(!PatternParser::is_pattern_data("x = 3, y = 3\nhello world!\n"));
+    assert!(!PatternParser::is_pattern_data("x = 5"));
+    assert!(!PatternParser::is_pattern_data("!Just a comment\n"));
+    assert!(!PatternParser::is_pattern_data("  \n"));
+}
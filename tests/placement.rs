@@ -0,0 +1,76 @@
+//! Tests of placing patterns on the cells already in the grid.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+fn text(life_grid: &LifeGrid) -> String {
+    life_grid.to_text(TextMode::Plain, false)
+}
+
+fn glider() -> Pattern {
+    Pattern::new(
+        vec![],
+        [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)]
+            .iter()
+            .map(|(row, col)| Cell::new(*row, *col))
+            .collect(),
+    )
+}
+
+/// A grid with its top left cell and third row populated.
+fn grid() -> LifeGrid {
+    let mut life_grid = LifeGrid::new_headless(60, 6);
+    life_grid.set_cell_state(0, 0, CellState::Populated);
+    (0..6).for_each(|col| life_grid.set_cell_state(2, col, CellState::Populated));
+    life_grid
+}
+
+#[test]
+fn modes_combine_with_the_cells_already_there() {
+    [
+        (PlacementMode::Or, "O.....\n..O...\nOOOOOO\n.OOO..\n"),
+        (PlacementMode::Xor, "O.....\n..O...\nOOO.OO\n.OOO..\n"),
+        (PlacementMode::AndNot, "O.....\n......\nOOO.OO\n......\n"),
+        // The pattern's bounding box is vacated first
+        (PlacementMode::Replace, "O.....\n..O...\nO..OOO\n.OOO..\n"),
+    ]
+    .iter()
+    .for_each(|(mode, expected)| {
+        let mut life_grid = grid();
+        let start = text(&life_grid);
+        assert_eq!(life_grid.place_pattern(&glider(), 1, 1, *mode, false), 0);
+        let expected = format!("{}{}", expected, "......\n".repeat(2));
+        assert_eq!(text(&life_grid), expected, "{:?}", mode);
+        // Placing is one step to undo
+        assert!(life_grid.undo());
+        assert_eq!(text(&life_grid), start, "{:?}", mode);
+    });
+}
+
+#[test]
+fn centered_patterns_and_clipped_cells() {
+    let mut life_grid = LifeGrid::new_headless(60, 6);
+    assert_eq!(
+        life_grid.place_pattern(&glider(), 3, 3, PlacementMode::Or, true),
+        0
+    );
+    assert_eq!(
+        text(&life_grid),
+        "......\n......\n...O..\n....O.\n..OOO.\n......\n"
+    );
+
+    // Only the cell in the grid is placed, and the rest are counted
+    life_grid.vacate_all_cells();
+    assert_eq!(
+        life_grid.place_pattern(&glider(), 5, 5, PlacementMode::Or, true),
+        4
+    );
+    assert_eq!(life_grid.population(), 1);
+    assert_eq!(life_grid.cell_state(4, 5), CellState::Populated);
+    assert_eq!(
+        life_grid.place_pattern(&glider(), 0, 6, PlacementMode::Or, false),
+        5
+    );
+    assert_eq!(life_grid.population(), 1);
+}
@@ -0,0 +1,112 @@
+//! Tests of drawing the grid into frame buffers.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_life::*;
+
+/// Draws into a frame buffer that the test can still read after handing the renderer over.
+struct SharedFrameBuffer(Rc<RefCell<FrameBuffer>>);
+
+impl Renderer for SharedFrameBuffer {
+    fn fill_rect(&mut self, x: f64, y: f64, width: f64, height: f64, color: Color) {
+        self.0.borrow_mut().fill_rect(x, y, width, height, color)
+    }
+    fn fill_rounded_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        radius: f64,
+        color: Color,
+    ) {
+        self.0
+            .borrow_mut()
+            .fill_rounded_rect(x, y, width, height, radius, color)
+    }
+    fn stroke_rect(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        line_width: f64,
+        color: Color,
+    ) {
+        self.0
+            .borrow_mut()
+            .stroke_rect(x, y, width, height, line_width, color)
+    }
+    fn fill_cells(&mut self, corners: &[(f64, f64)], size: f64, color: Color) {
+        self.0.borrow_mut().fill_cells(corners, size, color)
+    }
+    fn stroke_cells(&mut self, corners: &[(f64, f64)], size: f64, line_width: f64, color: Color) {
+        self.0
+            .borrow_mut()
+            .stroke_cells(corners, size, line_width, color)
+    }
+    fn clip(&mut self, x: f64, y: f64, width: f64, height: f64) {
+        self.0.borrow_mut().clip(x, y, width, height)
+    }
+    fn unclip(&mut self) {
+        self.0.borrow_mut().unclip()
+    }
+}
+
+fn glider(life_grid: &mut LifeGrid) {
+    [(1, 2), (2, 3), (3, 1), (3, 2), (3, 3)]
+        .iter()
+        .for_each(|(row, col)| life_grid.set_cell_state(*row, *col, CellState::Populated));
+}
+
+#[test]
+fn repainting_changed_cells_matches_a_full_draw() {
+    // Cells a whole number of pixels across and not, with and without the colours that change
+    // in cells that haven't, and with and without major grid lines and cell shapes
+    [
+        (300, 20, 0, CellShape::Square),
+        (300, 20, 5, CellShape::Rounded),
+        (317, 23, 4, CellShape::Circle),
+    ]
+    .iter()
+    .for_each(|(grid_size, cell_count, spacing, shape)| {
+        [
+            (false, OverlayKind::None),
+            (true, OverlayKind::Trail),
+            (false, OverlayKind::Heatmap),
+        ]
+        .iter()
+        .for_each(|(age_coloring, overlay)| {
+            let frame_buffer = Rc::new(RefCell::new(FrameBuffer::new(*grid_size, *grid_size, 1.0)));
+            let renderer = SharedFrameBuffer(frame_buffer.clone());
+            let mut life_grid =
+                LifeGrid::with_renderer(*grid_size, *cell_count, Box::new(renderer));
+            life_grid.set_age_coloring(*age_coloring);
+            life_grid.set_overlay(*overlay);
+            life_grid.set_major_grid_line_spacing(*spacing);
+            life_grid.set_cell_shape(*shape);
+            if *shape != CellShape::Square {
+                life_grid.set_cell_gap(2.0);
+            }
+            glider(&mut life_grid);
+            life_grid.draw();
+            (0..24).for_each(|generation| {
+                life_grid.evolve();
+                life_grid.draw();
+                let mut full = FrameBuffer::new(*grid_size, *grid_size, 1.0);
+                life_grid.draw_to(&mut full);
+                assert!(
+                    full.as_bytes() == frame_buffer.borrow().as_bytes(),
+                    "{} pixel grid of {} cells with {:?} and {:?}, generation {}",
+                    grid_size,
+                    cell_count,
+                    overlay,
+                    shape,
+                    generation + 1
+                );
+            });
+        });
+    });
+}
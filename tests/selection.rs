@@ -0,0 +1,210 @@
+//! Tests of selecting cells and the clipboard.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+const CELL_COUNT: u32 = 8;
+
+/// The rows and columns of the populated cells, in row-major order.
+fn live_cells(life_grid: &LifeGrid) -> Vec<(u32, u32)> {
+    life_grid
+        .live_cells_as_u32_array()
+        .iter()
+        .map(|index| (index / CELL_COUNT, index % CELL_COUNT))
+        .collect()
+}
+
+/// A grid with a cell populated in each corner and a block of four in the middle.
+fn corners_and_block() -> LifeGrid {
+    let mut life_grid = LifeGrid::new_headless(400, CELL_COUNT);
+    [
+        (0, 0),
+        (0, 7),
+        (3, 3),
+        (3, 4),
+        (4, 3),
+        (4, 4),
+        (7, 0),
+        (7, 7),
+    ]
+    .iter()
+    .for_each(|(row, col)| life_grid.set_cell_state(*row, *col, CellState::Populated));
+    life_grid
+}
+
+#[test]
+fn clearing_inside_the_selection() {
+    let mut life_grid = corners_and_block();
+    // Corners in either order
+    life_grid.select(&Cell::new(4, 4), &Cell::new(3, 2));
+    life_grid.vacate_selection();
+    assert_eq!(live_cells(&life_grid), vec![(0, 0), (0, 7), (7, 0), (7, 7)]);
+}
+
+#[test]
+fn clearing_outside_the_selection() {
+    let mut life_grid = corners_and_block();
+    life_grid.select(&Cell::new(3, 2), &Cell::new(4, 4));
+    life_grid.vacate_outside_selection();
+    assert_eq!(live_cells(&life_grid), vec![(3, 3), (3, 4), (4, 3), (4, 4)]);
+    // Without a selection nothing is cleared
+    life_grid.deselect();
+    life_grid.vacate_outside_selection();
+    assert_eq!(life_grid.population(), 4);
+}
+
+#[test]
+fn cut_then_paste() {
+    let mut life_grid = corners_and_block();
+    life_grid.select(&Cell::new(2, 2), &Cell::new(4, 4));
+    life_grid.cut_selection();
+    assert_eq!(life_grid.population(), 4);
+    let cells: Vec<(u32, u32)> = life_grid
+        .clipboard()
+        .unwrap()
+        .cells()
+        .iter()
+        .map(|cell| (cell.row(), cell.col()))
+        .collect();
+    assert_eq!(cells, vec![(1, 1), (1, 2), (2, 1), (2, 2)]);
+
+    life_grid.paste(0, 2);
+    assert_eq!(
+        live_cells(&life_grid),
+        vec![
+            (0, 0),
+            (0, 7),
+            (1, 3),
+            (1, 4),
+            (2, 3),
+            (2, 4),
+            (7, 0),
+            (7, 7)
+        ]
+    );
+    // The pasted cells are selected, from the clipboard's top left
+    let (min, max) = (
+        life_grid.selection_min().unwrap(),
+        life_grid.selection_max().unwrap(),
+    );
+    assert_eq!((min.row(), min.col(), max.row(), max.col()), (0, 2, 2, 4));
+}
+
+#[test]
+fn paste_at_an_edge_clips_cells() {
+    let mut life_grid = LifeGrid::new_headless(400, CELL_COUNT);
+    life_grid.set_clipboard(&Pattern::new(
+        vec![],
+        vec![
+            Cell::new(0, 0),
+            Cell::new(0, 2),
+            Cell::new(2, 0),
+            Cell::new(2, 2),
+        ],
+    ));
+    life_grid.paste(6, 6);
+    assert_eq!(live_cells(&life_grid), vec![(6, 6)]);
+    // The selection is limited to the grid
+    let (min, max) = (
+        life_grid.selection_min().unwrap(),
+        life_grid.selection_max().unwrap(),
+    );
+    assert_eq!((min.row(), min.col(), max.row(), max.col()), (6, 6, 7, 7));
+    life_grid.paste(u32::MAX, 0);
+    assert_eq!(life_grid.population(), 1);
+}
+
+/// A grid with three cells populated in a selection two rows high and four columns wide, and
+/// four around it.
+fn wide_selection() -> LifeGrid {
+    let mut life_grid = LifeGrid::new_headless(400, CELL_COUNT);
+    [(0, 0), (1, 1), (2, 1), (2, 2), (2, 6), (3, 4), (5, 5)]
+        .iter()
+        .for_each(|(row, col)| life_grid.set_cell_state(*row, *col, CellState::Populated));
+    life_grid.select(&Cell::new(2, 1), &Cell::new(3, 4));
+    life_grid
+}
+
+fn selection(life_grid: &LifeGrid) -> (u32, u32, u32, u32) {
+    let (min, max) = (
+        life_grid.selection_min().unwrap(),
+        life_grid.selection_max().unwrap(),
+    );
+    (min.row(), min.col(), max.row(), max.col())
+}
+
+#[test]
+fn rotating_the_selection() {
+    let mut life_grid = wide_selection();
+    let start = live_cells(&life_grid);
+    life_grid.rotate_clockwise();
+    // Only the selected cells turn, into a rectangle two columns wide and four rows high
+    assert_eq!(
+        live_cells(&life_grid),
+        vec![(0, 0), (1, 1), (1, 3), (2, 3), (2, 6), (4, 2), (5, 5)]
+    );
+    assert_eq!(selection(&life_grid), (1, 2, 4, 3));
+    // Turning back returns the rectangle to where it was
+    life_grid.rotate_counter_clockwise();
+    assert_eq!(live_cells(&life_grid), start);
+    assert_eq!(selection(&life_grid), (2, 1, 3, 4));
+    (0..4).for_each(|_| life_grid.rotate_clockwise());
+    assert_eq!(live_cells(&life_grid), start);
+    assert_eq!(selection(&life_grid), (2, 1, 3, 4));
+
+    // Also when the height and width differ by an odd number
+    life_grid.select(&Cell::new(5, 0), &Cell::new(5, 3));
+    life_grid.set_cell_state(5, 0, CellState::Populated);
+    life_grid.rotate_counter_clockwise();
+    assert_eq!(selection(&life_grid), (4, 2, 7, 2));
+    assert_eq!(life_grid.cell_state(7, 2), CellState::Populated);
+    life_grid.rotate_clockwise();
+    assert_eq!(selection(&life_grid), (5, 0, 5, 3));
+    assert_eq!(life_grid.cell_state(5, 0), CellState::Populated);
+    assert_eq!(life_grid.population(), start.len() as u32 + 1);
+}
+
+#[test]
+fn rotating_the_selection_keeps_cells_outside_it() {
+    let mut life_grid = wide_selection();
+    // A populated cell where a selected one would turn to
+    life_grid.set_cell_state(4, 3, CellState::Populated);
+    let start = live_cells(&life_grid);
+    life_grid.rotate_clockwise();
+    assert_eq!(live_cells(&life_grid), start);
+    assert_eq!(selection(&life_grid), (2, 1, 3, 4));
+
+    // Nor does a selection turn past the edge of the grid
+    life_grid.select(&Cell::new(0, 0), &Cell::new(0, 3));
+    life_grid.rotate_counter_clockwise();
+    assert_eq!(live_cells(&life_grid), start);
+    assert_eq!(selection(&life_grid), (0, 0, 0, 3));
+}
+
+#[test]
+fn flipping_and_shifting_the_selection() {
+    let mut life_grid = wide_selection();
+    life_grid.flip_horizontal();
+    assert_eq!(
+        live_cells(&life_grid),
+        vec![(0, 0), (1, 1), (2, 4), (2, 6), (3, 1), (3, 2), (5, 5)]
+    );
+    life_grid.flip_vertical();
+    assert_eq!(
+        live_cells(&life_grid),
+        vec![(0, 0), (1, 1), (2, 1), (2, 6), (3, 3), (3, 4), (5, 5)]
+    );
+    // Cells shifted past one side of the selection come back in the other
+    life_grid.shift_right();
+    assert_eq!(
+        live_cells(&life_grid),
+        vec![(0, 0), (1, 1), (2, 2), (2, 6), (3, 1), (3, 4), (5, 5)]
+    );
+    life_grid.shift_up();
+    assert_eq!(
+        live_cells(&life_grid),
+        vec![(0, 0), (1, 1), (2, 1), (2, 4), (2, 6), (3, 2), (5, 5)]
+    );
+    assert_eq!(selection(&life_grid), (2, 1, 3, 4));
+}
@@ -0,0 +1,48 @@
+//! Tests of the population statistics, the bulk cell accessors and the whole-grid transforms.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use wasm_life::*;
+
+#[test]
+fn bulk_accessors_round_trip() {
+    let mut life_grid = LifeGrid::new_headless(400, 3);
+    life_grid.set_cells_from_bytes(&[0, 1, 0, 0, 0, 7]);
+    assert_eq!(life_grid.live_cells_as_u32_array(), vec![1, 5]);
+    assert_eq!(life_grid.cells_len(), 9);
+    let cells = unsafe { std::slice::from_raw_parts(life_grid.cells_ptr(), 9) };
+    assert_eq!(cells, &[0, 1, 0, 0, 0, 1, 0, 0, 0]);
+}
+
+#[test]
+fn statistics_follow_edits_and_evolve() {
+    let mut life_grid = LifeGrid::new_headless(400, 10);
+    assert_eq!(life_grid.population(), 0);
+    assert!(life_grid.bounding_box_min().is_none());
+    // A horizontal blinker
+    (3..6).for_each(|col| life_grid.set_cell_state(4, col, CellState::Populated));
+    assert_eq!(life_grid.population(), 3);
+    let (min, max) = (
+        life_grid.bounding_box_min().unwrap(),
+        life_grid.bounding_box_max().unwrap(),
+    );
+    assert_eq!((min.row(), min.col(), max.row(), max.col()), (4, 3, 4, 5));
+    life_grid.evolve();
+    assert_eq!((life_grid.births(), life_grid.deaths()), (2, 2));
+    assert_eq!(life_grid.population(), 3);
+    let (min, max) = (
+        life_grid.bounding_box_min().unwrap(),
+        life_grid.bounding_box_max().unwrap(),
+    );
+    assert_eq!((min.row(), min.col(), max.row(), max.col()), (3, 4, 5, 4));
+}
+
+#[test]
+fn transforming_an_empty_grid_does_nothing() {
+    let mut life_grid = LifeGrid::new_headless(400, 10);
+    life_grid.rotate_clockwise();
+    life_grid.rotate_counter_clockwise();
+    life_grid.flip_horizontal();
+    life_grid.flip_vertical();
+    assert_eq!(life_grid.population(), 0);
+}
@@ -4,6 +4,7 @@
 
 extern crate wasm_bindgen_test;
 use wasm_bindgen_test::*;
+use wasm_life::*;
 
 wasm_bindgen_test_configure!(run_in_browser);
 
@@ -11,3 +12,75 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+/// A scattering of about a third of the cells, the same on every run.
+fn soup(cell_count: u32) -> Vec<bool> {
+    let mut seed: u32 = 12345;
+    (0..cell_count * cell_count)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16).is_multiple_of(3)
+        })
+        .collect()
+}
+
+/// The generation that follows `cells`, with the cells outside the grid vacant.
+fn reference_step(cells: &[bool], cell_count: u32) -> Vec<bool> {
+    let count = cell_count as i32;
+    let populated = |row: i32, col: i32| {
+        (0..count).contains(&row)
+            && (0..count).contains(&col)
+            && cells[(row * count + col) as usize]
+    };
+    (0..count * count)
+        .map(|index| {
+            let (row, col) = (index / count, index % count);
+            let neighbors = (-1..=1)
+                .flat_map(|row_offset| (-1..=1).map(move |col_offset| (row_offset, col_offset)))
+                .filter(|offsets| *offsets != (0, 0))
+                .filter(|(row_offset, col_offset)| populated(row + row_offset, col + col_offset))
+                .count();
+            neighbors == 3 || (neighbors == 2 && populated(row, col))
+        })
+        .collect()
+}
+
+/// Evolves a soup for 20 generations with `engine_kind`, checking every cell against
+/// `reference_step`.
+fn assert_evolves_like_reference(cell_count: u32, engine_kind: EngineKind, thread_count: u32) {
+    let mut life_grid = LifeGrid::new_headless(400, cell_count);
+    life_grid.set_engine(engine_kind);
+    life_grid.set_thread_count(thread_count);
+    let mut cells = soup(cell_count);
+    (0..cell_count * cell_count)
+        .filter(|index| cells[*index as usize])
+        .for_each(|index| {
+            life_grid.set_cell_state(index / cell_count, index % cell_count, CellState::Populated)
+        });
+    (0..20).for_each(|_| {
+        life_grid.evolve();
+        cells = reference_step(&cells, cell_count);
+        (0..cell_count * cell_count).for_each(|index| {
+            let expected = if cells[index as usize] {
+                CellState::Populated
+            } else {
+                CellState::Vacant
+            };
+            assert_eq!(
+                life_grid.cell_state(index / cell_count, index % cell_count),
+                expected
+            );
+        });
+    });
+}
+
+/// Checks the SIMD kernel, which handles 16 columns at a time, on widths that are and aren't
+/// multiples of 16. Run it with
+/// `RUSTFLAGS='-C target-feature=+simd128' wasm-pack test --headless --firefox -- --features simd`;
+/// without those flags it checks the scalar kernel.
+#[wasm_bindgen_test]
+fn simd_matches_reference_step() {
+    [16, 32, 37, 48, 50]
+        .iter()
+        .for_each(|cell_count| assert_evolves_like_reference(*cell_count, EngineKind::Naive, 1));
+}
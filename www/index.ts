@@ -3,20 +3,164 @@ import "./styles/style.css";
 
 const CELL_COUNT = 100;
 const GRID_SIZE = 900;
+const MINIMAP_SIZE = 150;
 
 let running = false;
 let time = 100;
 let timeout_id = 0;
 let generations = 0;
+let dragStart: { x: number, y: number } | null = null;
+let dragged = false;
+let selectStart: wasm.Cell | null = null;
+let pointerCell: wasm.Cell | null = null;
+let toolStart: wasm.Cell | null = null;
 
+const tool = () => (document.getElementById("tool") as HTMLSelectElement).value;
 const handleClickInGrid = (event: MouseEvent) => {
-    if (running) return;
+    if (running || dragged) return;
 
-    const canvas = event.target as HTMLCanvasElement;
-    const cell = life_grid.cell_from_point(event.clientX - canvas.offsetLeft, event.clientY - canvas.offsetTop);
+    const cell = life_grid.cell_from_point(event.clientX, event.clientY);
+    if (!cell) return;
+    if (tool() === "toggle") {
+        clearGenerations();
+        life_grid.toggle_cell_state(cell.row(), cell.col());
+        draw();
+    } else if (tool() === "fill") {
+        clearGenerations();
+        const state = life_grid.cell_state(cell.row(), cell.col()) === wasm.CellState.Populated
+            ? wasm.CellState.Vacant
+            : wasm.CellState.Populated;
+        life_grid.flood_fill(cell.row(), cell.col(), state);
+        draw();
+    }
+}
+// Draws the current tool's shape from one cell to another, vacating the cells instead with Alt
+const drawShape = (from: wasm.Cell, to: wasm.Cell, vacate: boolean) => {
+    const state = vacate ? wasm.CellState.Vacant : wasm.CellState.Populated;
+    switch (tool()) {
+        case "line":
+            life_grid.draw_line(from, to, state);
+            break;
+        case "rectangle":
+        case "filledRectangle":
+            life_grid.draw_rectangle(from, to, tool() === "filledRectangle", state);
+            break;
+        case "ellipse":
+        case "filledEllipse":
+            life_grid.draw_ellipse(from, to, tool() === "filledEllipse", state);
+            break;
+    }
     clearGenerations();
-    life_grid.toggle_cell_state(cell.row(), cell.col());
-    life_grid.draw();
+    draw();
+}
+const handleClickInMinimap = (event: MouseEvent) => {
+    life_grid.minimap_jump(event.clientX, event.clientY);
+    draw();
+}
+const handleWheelInGrid = (event: WheelEvent) => {
+    event.preventDefault();
+    life_grid.zoom_at(event.clientX, event.clientY, event.deltaY < 0 ? 1.25 : 0.8);
+    draw();
+}
+const handleMouseDownInGrid = (event: MouseEvent) => {
+    dragStart = { x: event.clientX, y: event.clientY };
+    dragged = false;
+    // Shift-drag selects instead of panning
+    selectStart = event.shiftKey ? life_grid.cell_from_point(event.clientX, event.clientY) ?? null : null;
+    // Shape tools drag out their shape instead of panning
+    const shapeTool = tool() !== "toggle" && tool() !== "fill";
+    toolStart = shapeTool && !event.shiftKey && !running
+        ? life_grid.cell_from_point(event.clientX, event.clientY) ?? null
+        : null;
+}
+const handleMouseMoveInGrid = (event: MouseEvent) => {
+    pointerCell = life_grid.cell_from_point(event.clientX, event.clientY) ?? null;
+}
+const handleMouseMove = (event: MouseEvent) => {
+    if (!dragStart) return;
+    if (selectStart) {
+        const cell = life_grid.cell_from_point(event.clientX, event.clientY);
+        if (cell) {
+            dragged = true;
+            life_grid.select(selectStart, cell);
+            draw();
+        }
+        return;
+    }
+    if (toolStart) {
+        dragged = true;
+        return;
+    }
+    const dx = event.clientX - dragStart.x;
+    const dy = event.clientY - dragStart.y;
+    // Ignore small movements so that clicks still toggle cells
+    if (!dragged && Math.abs(dx) < 4 && Math.abs(dy) < 4) return;
+    dragged = true;
+    dragStart = { x: event.clientX, y: event.clientY };
+    life_grid.pan_by(dx, dy);
+    draw();
+}
+const handleMouseUp = (event: MouseEvent) => {
+    if (toolStart) {
+        const end = life_grid.cell_from_point(event.clientX, event.clientY) ?? pointerCell;
+        if (end) drawShape(toolStart, end, event.altKey);
+    }
+    dragStart = null;
+    selectStart = null;
+    toolStart = null;
+}
+// Puts the internal clipboard on the system clipboard as RLE
+const exportClipboard = () => {
+    const pattern = life_grid.clipboard();
+    if (pattern) navigator.clipboard?.writeText(wasm.PatternWriter.to_rle_data(pattern)).catch(() => {});
+}
+const copySelection = () => {
+    life_grid.copy_selection();
+    exportClipboard();
+}
+const cutSelection = () => {
+    life_grid.cut_selection();
+    exportClipboard();
+    draw();
+}
+const paste = async () => {
+    // At the cell under the pointer, or else the top left of the selection
+    const cell = pointerCell ?? life_grid.selection_min();
+    if (!cell) return;
+    // Prefer RLE or plaintext from the system clipboard, if the browser allows reading it and
+    // it holds a pattern rather than other text
+    try {
+        const text = await navigator.clipboard.readText();
+        if (wasm.PatternParser.is_pattern_data(text)) {
+            life_grid.set_clipboard(wasm.PatternParser.parse_data(text));
+        }
+    } catch {
+        // Paste the internal clipboard
+    }
+    life_grid.paste(cell.row(), cell.col());
+    draw();
+}
+const vacateSelection = () => {
+    life_grid.vacate_selection();
+    draw();
+}
+const vacateOutsideSelection = () => {
+    life_grid.vacate_outside_selection();
+    draw();
+}
+const undo = () => {
+    stop();
+    life_grid.undo();
+    draw();
+}
+const redo = () => {
+    stop();
+    life_grid.redo();
+    draw();
+}
+const deselect = () => {
+    life_grid.deselect();
+    draw();
 }
 const insertRandomPattern = () => {
     const random = (min: number, max: number)=> {
@@ -38,64 +182,85 @@ const step = () => {
     stop();
     evolve();
 }
+// Places a loaded pattern, centred on the selection or else the grid, combined with the cells
+// already there by the chosen mode
 const insertPattern = (pattern: wasm.Pattern)=> {
-    // console.log(pattern.cells())
-    // console.log(pattern.metadata())
-    insertCells(pattern.cells());
+    const modes: { [key: string]: wasm.PlacementMode } = {
+        or: wasm.PlacementMode.Or,
+        xor: wasm.PlacementMode.Xor,
+        andNot: wasm.PlacementMode.AndNot,
+        replace: wasm.PlacementMode.Replace,
+    };
+    const mode = modes[(document.getElementById("placementMode") as HTMLSelectElement).value];
+    const min = life_grid.selection_min();
+    const max = life_grid.selection_max();
+    const row = min && max ? Math.floor((min.row() + max.row()) / 2) : Math.floor(CELL_COUNT / 2);
+    const col = min && max ? Math.floor((min.col() + max.col()) / 2) : Math.floor(CELL_COUNT / 2);
+    const clipped = life_grid.place_pattern(pattern, row, col, mode, true);
+    if (clipped > 0) console.warn(`${clipped} cells of the pattern are outside the grid`);
+    draw();
 }
 const insertCells = (cells: wasm.Cell[]) => {
-    life_grid.vacate_all_cells();
+    const bytes = new Uint8Array(CELL_COUNT * CELL_COUNT);
     for(var i = 0; i < cells.length; ++i) {
         // console.log(`${i}: ${cells[i].row()} ${cells[i].col()}`)
-        life_grid.set_cell_state(cells[i].row(), cells[i].col(), wasm.CellState.Populated);
+        const row = cells[i].row();
+        const col = cells[i].col();
+        if (row < CELL_COUNT && col < CELL_COUNT) bytes[row * CELL_COUNT + col] = 1;
     }
-    life_grid.draw();
+    life_grid.set_cells_from_bytes(bytes);
+    draw();
 }
 const clearGrid = () => {
     stop();
     clearGenerations();
     life_grid.vacate_all_cells();
-    life_grid.draw();
+    draw();
 }
 const rotateClockwise = () => {
     stop();
     life_grid.rotate_clockwise();
-    life_grid.draw();
+    draw();
 }
 const rotateCounterClockwise = () => {
     stop();
     life_grid.rotate_counter_clockwise();
-    life_grid.draw();
+    draw();
 }
 const flipHorizontal = () => {
     stop();
     life_grid.flip_horizontal();
-    life_grid.draw();
+    draw();
 }
 const flipVertical = () => {
     stop();
     life_grid.flip_vertical();
-    life_grid.draw();
+    draw();
 }
 const shiftUp = () => {
     stop();
     life_grid.shift_up();
-    life_grid.draw();
+    draw();
 }
 const shiftDown = () => {
     stop();
     life_grid.shift_down();
-    life_grid.draw();
+    draw();
 }
 const shiftLeft = () => {
     stop();
     life_grid.shift_left();
-    life_grid.draw();
+    draw();
 }
 const shiftRight = () => {
     stop();
     life_grid.shift_right();
+    draw();
+}
+const draw = () => {
     life_grid.draw();
+    (document.getElementById("population") as HTMLSpanElement).innerText =
+        `Population: ${life_grid.population()} (+${life_grid.births()} -${life_grid.deaths()})`;
 }
 const clearGenerations = () => setGenerations(0);
 const bumpGenerations = () => setGenerations(generations + 1);
@@ -106,7 +271,7 @@ const setGenerations = (value: number)=> {
 const evolve = () => {
     bumpGenerations();
     life_grid.evolve();
-    life_grid.draw();
+    draw();
     if (running) timeout_id = setTimeout(evolve, time);
 }
 const startStop = () => {
@@ -148,7 +313,71 @@ const handleFile = (event: Event) => {
         reader.readAsText(file);
     }
 }
+const handleEngineChange = (event: Event) => {
+    const select = event.target as HTMLSelectElement;
+    life_grid.set_engine(select.value === "quickLife" ? wasm.EngineKind.QuickLife : wasm.EngineKind.Naive);
+}
+const handleThemeChange = (event: Event) => {
+    const select = event.target as HTMLSelectElement;
+    const theme = wasm.Theme.named(select.value);
+    if (theme) life_grid.set_theme(theme);
+}
+const setAgeColoring = (flag: boolean) => {
+    (document.getElementById("ageColoring") as HTMLInputElement).checked = flag;
+    life_grid.set_age_coloring(flag);
+    draw();
+}
+const handleOverlayChange = (event: Event) => {
+    const overlays: { [key: string]: wasm.OverlayKind } = {
+        none: wasm.OverlayKind.None,
+        trail: wasm.OverlayKind.Trail,
+        heatmap: wasm.OverlayKind.Heatmap,
+    };
+    life_grid.set_overlay(overlays[(event.target as HTMLSelectElement).value]);
+    draw();
+}
+const handleCellShapeChange = (event: Event) => {
+    const shapes: { [key: string]: wasm.CellShape } = {
+        square: wasm.CellShape.Square,
+        rounded: wasm.CellShape.Rounded,
+        circle: wasm.CellShape.Circle,
+    };
+    life_grid.set_cell_shape(shapes[(event.target as HTMLSelectElement).value]);
+    draw();
+}
+const download = (blob: Blob, fileName: string) => {
+    const link = document.createElement("a");
+    link.href = URL.createObjectURL(blob);
+    link.download = fileName;
+    link.click();
+    URL.revokeObjectURL(link.href);
+}
+const cropExport = () => (document.getElementById("cropExport") as HTMLInputElement).checked;
+const saveSvg = () => {
+    download(new Blob([life_grid.to_svg(cropExport())], { type: "image/svg+xml" }), "life.svg");
+}
+const numberInput = (id: string) => Math.max(1, Number((document.getElementById(id) as HTMLInputElement).value));
+const savePng = () => {
+    download(new Blob([life_grid.to_png(numberInput("pngScale"), cropExport())], { type: "image/png" }), "life.png");
+}
+const saveGif = () => {
+    const gif = life_grid.to_gif(numberInput("gifFrames"), numberInput("gifDelay"), numberInput("pngScale"), cropExport());
+    download(new Blob([gif], { type: "image/gif" }), "life.gif");
+}
+const showText = () => {
+    const modes: { [key: string]: wasm.TextMode } = {
+        plain: wasm.TextMode.Plain,
+        halfBlock: wasm.TextMode.HalfBlock,
+        braille: wasm.TextMode.Braille,
+    };
+    const mode = modes[(document.getElementById("textMode") as HTMLSelectElement).value];
+    (document.getElementById("pattern") as HTMLTextAreaElement).value = life_grid.to_text(mode, cropExport());
+}
 const handleKeyup = (event: KeyboardEvent) => {
+    // Leave typing in text fields alone
+    if (event.target instanceof HTMLInputElement || event.target instanceof HTMLTextAreaElement) return;
+    // Shortcuts with Ctrl are handled on keydown
+    if (event.ctrlKey || event.metaKey) return;
     // Non-repeating keys
     switch (event.key) {
         case " ":
@@ -161,6 +390,13 @@ const handleKeyup = (event: KeyboardEvent) => {
         case "c":
             clearGrid();
             break;
+        case "a":
+            setAgeColoring(!life_grid.age_coloring());
+            break;
+        case "0":
+            life_grid.reset_viewport();
+            draw();
+            break;
         case "F3":
             flipHorizontal();
             break;
@@ -176,6 +412,36 @@ const handleKeyup = (event: KeyboardEvent) => {
     }
 }
 const handleKeydown = (event: KeyboardEvent) => {
+    // Leave text fields' own shortcuts alone
+    if (event.target instanceof HTMLInputElement || event.target instanceof HTMLTextAreaElement) return;
+    if (event.ctrlKey || event.metaKey) {
+        switch (event.key) {
+            case "a":
+                event.preventDefault();
+                life_grid.select_all();
+                draw();
+                break;
+            case "c":
+                copySelection();
+                break;
+            case "x":
+                cutSelection();
+                break;
+            case "v":
+                paste();
+                break;
+            case "z":
+            case "Z":
+                event.preventDefault();
+                if (event.shiftKey) redo(); else undo();
+                break;
+            case "y":
+                event.preventDefault();
+                redo();
+                break;
+        }
+        return;
+    }
     // Repeating keys
     switch (event.key) {
         case "ArrowUp":
@@ -193,9 +459,21 @@ const handleKeydown = (event: KeyboardEvent) => {
         case "s":
             step();
             break;
+        case "Delete":
+            vacateSelection();
+            break;
+        case "Escape":
+            deselect();
+            break;
     }
 }
 document.getElementById("canvas")?.addEventListener('click', handleClickInGrid);
+document.getElementById("canvas")?.addEventListener('wheel', handleWheelInGrid);
+document.getElementById("canvas")?.addEventListener('mousedown', handleMouseDownInGrid);
+document.getElementById("canvas")?.addEventListener('mousemove', handleMouseMoveInGrid);
+document.getElementById("minimap")?.addEventListener('click', handleClickInMinimap);
+document.addEventListener("mousemove", handleMouseMove);
+document.addEventListener("mouseup", handleMouseUp);
 document.getElementById("random")?.addEventListener('click', insertRandomPattern);
 document.getElementById("clear")?.addEventListener('click', clearGrid);
 document.getElementById("step")?.addEventListener('click', step);
@@ -209,11 +487,52 @@ document.getElementById("shiftUp")?.addEventListener('click', shiftUp);
 document.getElementById("shiftDown")?.addEventListener('click', shiftDown);
 document.getElementById("shiftRight")?.addEventListener('click', shiftRight);
 document.getElementById("shiftLeft")?.addEventListener('click', shiftLeft);
+document.getElementById("undo")?.addEventListener('click', undo);
+document.getElementById("redo")?.addEventListener('click', redo);
+document.getElementById("copy")?.addEventListener('click', copySelection);
+document.getElementById("cut")?.addEventListener('click', cutSelection);
+document.getElementById("paste")?.addEventListener('click', paste);
+document.getElementById("vacateSelection")?.addEventListener('click', vacateSelection);
+document.getElementById("vacateOutside")?.addEventListener('click', vacateOutsideSelection);
 document.getElementById("docPicker")?.addEventListener('change', handleFile);
+document.getElementById("engine")?.addEventListener('change', handleEngineChange);
+document.getElementById("theme")?.addEventListener('change', handleThemeChange);
+document.getElementById("overlay")?.addEventListener('change', handleOverlayChange);
+document.getElementById("ageColoring")?.addEventListener('change',
+    (event: Event) => setAgeColoring((event.target as HTMLInputElement).checked));
+document.getElementById("gridLines")?.addEventListener('change', (event: Event) => {
+    life_grid.set_grid_lines((event.target as HTMLInputElement).checked);
+    draw();
+});
+document.getElementById("majorGridLines")?.addEventListener('change', (event: Event) => {
+    life_grid.set_major_grid_line_spacing(Math.max(0, Number((event.target as HTMLInputElement).value)));
+    draw();
+});
+document.getElementById("cellShape")?.addEventListener('change', handleCellShapeChange);
+document.getElementById("saveSvg")?.addEventListener('click', saveSvg);
+document.getElementById("savePng")?.addEventListener('click', savePng);
+document.getElementById("saveGif")?.addEventListener('click', saveGif);
+document.getElementById("showText")?.addEventListener('click', showText);
+document.getElementById("cellGap")?.addEventListener('input', (event: Event) => {
+    life_grid.set_cell_gap(Number((event.target as HTMLInputElement).value));
+    draw();
+});
 document.addEventListener("keydown", handleKeydown);
+window.addEventListener("resize", () => {
+    life_grid.refresh_pixel_ratio();
+    draw();
+});
 document.addEventListener("keyup", handleKeyup);
 
 const life_grid = wasm.LifeGrid.new(GRID_SIZE, CELL_COUNT, "canvas");
+life_grid.set_minimap("minimap", MINIMAP_SIZE);
+// Only the wasm-threads build exports initThreadPool, and it needs a cross-origin isolated page
+type ThreadedModule = { initThreadPool?: (threads: number) => Promise<void> };
+const initThreadPool = (wasm as unknown as ThreadedModule).initThreadPool;
+if (initThreadPool && self.crossOriginIsolated) {
+    initThreadPool(navigator.hardwareConcurrency)
+        .then(() => life_grid.set_thread_count(navigator.hardwareConcurrency));
+}
 stop();
-life_grid.draw();
+draw();
 clearGenerations();
@@ -29,6 +29,13 @@ module.exports = {
         filename: "bootstrap.js",
     },
     mode: "production",
+    devServer: {
+        // Cross-origin isolation, which wasm threads need for SharedArrayBuffer
+        headers: {
+            "Cross-Origin-Opener-Policy": "same-origin",
+            "Cross-Origin-Embedder-Policy": "require-corp",
+        },
+    },
     plugins: [
         new MiniCssExtractPlugin(),
         new HtmlWebpackPlugin({